@@ -0,0 +1,178 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, Role, RoleId};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::handlers::misc::auto_role::PendingAutoRole;
+use crate::utils::{CommandResult, Context};
+use crate::utils::template::format_duration;
+
+/// # Rol que se asigna automáticamente a los miembros nuevos
+///
+/// - `delay` es el tiempo en segundos que se espera antes de asignar el rol
+/// - `min_account_age` es la antigüedad mínima de la cuenta en días para recibir el rol
+/// - `bots` indica si el rol es para los bots en lugar de para los usuarios
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AutoRole {
+    pub guild_id: GuildId,
+    pub role_id: RoleId,
+    pub delay: i64,
+    pub min_account_age: Option<i64>,
+    pub bots: bool,
+}
+
+impl AutoRole {
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("auto_role")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_roles(guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM auto_role WHERE guild_id = $guild_id";
+        let roles: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .await?
+            .take(0)?;
+
+        Ok(roles)
+    }
+
+    pub async fn remove_role(guild_id: GuildId, role_id: RoleId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE auto_role WHERE guild_id = $guild_id AND role_id = $role_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("role_id", role_id))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Describe el rol en una línea para los listados
+    pub fn describe(&self) -> String {
+        let target = if self.bots { "bots" } else { "usuarios" };
+        let delay = if self.delay == 0 { "al entrar".to_string() } else { format!("tras {}", format_duration(self.delay)) };
+        let age = self.min_account_age.map_or_else(String::new, |days| format!(", cuentas de al menos {days} días"));
+
+        format!("<@&{}>: {target}, {delay}{age}", self.role_id)
+    }
+}
+
+/// Administra los roles que se asignan automáticamente a los miembros nuevos
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral,
+    subcommands("autorole_add", "autorole_remove", "autorole_list"),
+    subcommand_required
+)]
+pub async fn autorole(_: Context<'_>) -> CommandResult {
+    Ok(())
+}
+
+/// Añade un rol que se asigna automáticamente al entrar al servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "add",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn autorole_add(
+    ctx: Context<'_>,
+    #[description = "Rol que se asigna"] role: Role,
+    #[description = "Minutos que se esperan antes de asignar el rol (por defecto: 0)"]
+    #[max = 40320]
+    delay: Option<u32>,
+    #[description = "Asignar el rol solo a las cuentas con al menos estos días de antigüedad"]
+    #[min = 1]
+    #[max = 3650]
+    min_account_age: Option<u32>,
+    #[description = "Asignar el rol a los bots en lugar de a los usuarios (por defecto: false)"] bots: Option<bool>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    if role.managed {
+        ctx.say("No se puede asignar un rol gestionado por una integración").await?;
+        return Ok(())
+    }
+
+    let auto_role = AutoRole {
+        guild_id,
+        role_id: role.id,
+        delay: delay.map_or(0, |minutes| i64::from(minutes) * 60),
+        min_account_age: min_account_age.map(i64::from),
+        bots: bots.unwrap_or(false),
+    };
+
+    // Se elimina antes de guardarlo para no duplicar el rol
+    AutoRole::remove_role(guild_id, role.id).await?;
+    auto_role.save_to_db().await?;
+    ctx.say(format!("Se ha añadido el rol automático {}", auto_role.describe())).await?;
+
+    Ok(())
+}
+
+/// Elimina un rol automático y cancela sus asignaciones pendientes
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "remove",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn autorole_remove(
+    ctx: Context<'_>,
+    #[description = "Rol a eliminar"] role: Role,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    AutoRole::remove_role(guild_id, role.id).await?;
+    PendingAutoRole::remove_role(guild_id, role.id).await?;
+    ctx.say(format!("Se ha eliminado el rol automático <@&{}>", role.id)).await?;
+
+    Ok(())
+}
+
+/// Muestra los roles automáticos del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "list",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn autorole_list(ctx: Context<'_>) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let roles = AutoRole::get_roles(guild_id).await?;
+
+    if roles.is_empty() {
+        ctx.say("No hay roles automáticos").await?;
+        return Ok(())
+    }
+
+    let mut description = "**Roles automáticos:**\n".to_string();
+    for role in &roles {
+        writeln!(description, "- {}", role.describe())?;
+    }
+
+    ctx.say(description).await?;
+
+    Ok(())
+}
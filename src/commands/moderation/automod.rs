@@ -0,0 +1,424 @@
+use std::fmt::Write;
+
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, GuildId, Role, RoleId};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::handlers::misc::word_filter::compile_rule_regex;
+use crate::utils::autocomplete::automod_rules;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{Automod, GuildData};
+
+/// # Disparador de una regla de moderación automática
+///
+/// - `Mention`: Mención a alguno de los usuarios o roles indicados (también `everyone` y `here`)
+/// - `Regex`: Expresión regular, comparada con el texto normalizado
+/// - `Link`: Enlace a alguno de los dominios indicados, o a cualquier dominio si no se indica ninguno
+/// - `Attachment`: Archivo adjunto de alguno de los tipos o extensiones indicados, o cualquier archivo
+/// - `LinkSpam`: El mismo enlace en varios canales, según los límites de `set_spam_thresholds`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum AutomodTrigger {
+    #[name = "Mención"]
+    Mention,
+    #[name = "Expresión regular"]
+    Regex,
+    #[name = "Enlace"]
+    Link,
+    #[name = "Archivo adjunto"]
+    Attachment,
+    #[name = "Spam de enlaces"]
+    LinkSpam,
+}
+
+/// # Acción de una regla de moderación automática
+///
+/// - `Delete`: Se borra el mensaje
+/// - `Warn`: Se borra el mensaje y se suma una advertencia
+/// - `Timeout`: Se silencia al autor
+/// - `Role`: Se le asigna un rol al autor
+/// - `Log`: Se registra en el canal de Logs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum AutomodAction {
+    #[name = "Borrar"]
+    Delete,
+    #[name = "Advertir"]
+    Warn,
+    #[name = "Silenciar"]
+    Timeout,
+    #[name = "Asignar rol"]
+    Role,
+    #[name = "Registrar"]
+    Log,
+}
+
+impl AutomodAction {
+    /// Convierte el nombre de una acción (`delete`, `warn`, `timeout`, `role`, `log`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "delete" => Some(Self::Delete),
+            "warn" => Some(Self::Warn),
+            "timeout" => Some(Self::Timeout),
+            "role" => Some(Self::Role),
+            "log" => Some(Self::Log),
+            _ => None,
+        }
+    }
+}
+
+/// # Regla de moderación automática de un servidor
+///
+/// - `value` depende del disparador: menciones, expresión regular, dominios o tipos de archivo
+/// - `channels` y `roles` limitan la regla a esos canales o a los autores con alguno de esos roles
+/// - `max_account_age` limita la regla a las cuentas con menos horas de antigüedad
+/// - `timeout` es la duración del silencio en segundos; si no se indica, se usa la del servidor
+/// - `role` es el rol que se asigna con la acción `Role`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AutomodRule {
+    pub guild_id: GuildId,
+    pub name: String,
+    pub trigger: AutomodTrigger,
+    pub value: String,
+    pub channels: Vec<ChannelId>,
+    pub roles: Vec<RoleId>,
+    pub max_account_age: Option<i64>,
+    pub exempt_channels: Vec<ChannelId>,
+    pub exempt_roles: Vec<RoleId>,
+    pub actions: Vec<AutomodAction>,
+    pub timeout: Option<i64>,
+    pub role: Option<RoleId>,
+}
+
+impl AutomodRule {
+    pub fn new(guild_id: GuildId, name: &str, trigger: AutomodTrigger, value: &str, actions: Vec<AutomodAction>) -> Self {
+        Self {
+            guild_id,
+            name: name.to_string(),
+            trigger,
+            value: value.to_string(),
+            channels: Vec::new(),
+            roles: Vec::new(),
+            max_account_age: None,
+            exempt_channels: Vec::new(),
+            exempt_roles: Vec::new(),
+            actions,
+            timeout: None,
+            role: None,
+        }
+    }
+
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("automod_rule")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_rules(guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM automod_rule WHERE guild_id = $guild_id";
+        let rules: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .await?
+            .take(0)?;
+
+        Ok(rules)
+    }
+
+    pub async fn remove_rule(guild_id: GuildId, name: &str) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE automod_rule WHERE guild_id = $guild_id AND name = $name";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("name", name))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Describe la regla en una línea para los listados y el canal de Logs
+    pub fn describe(&self) -> String {
+        let actions = self.actions
+            .iter()
+            .map(|action| action.name())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let value = if self.value.is_empty() { "cualquiera" } else { &self.value };
+        format!("`{}`: {} `{value}` → {actions}", self.name, self.trigger.name())
+    }
+}
+
+/// # Crea las reglas que reproducen la moderación que ya tenía el servidor
+///
+/// - Mención al usuario prohibido: advertencia
+/// - Mención a los miembros con el rol prohibido: advertencia
+/// - `@everyone` y `@here`: borrado y silencio
+/// - Spam de enlaces en varios canales: borrado en todos los canales y silencio,
+///   usando el mismo rastreador que el módulo de spam
+pub fn default_rules(guild_id: GuildId, guild_data: Option<&GuildData>) -> Vec<AutomodRule> {
+    let forbidden = guild_data.map(|data| data.forbidden.clone()).unwrap_or_default();
+    let mut rules = Vec::new();
+
+    if let Some(user) = forbidden.user {
+        rules.push(AutomodRule::new(guild_id, "usuario-prohibido", AutomodTrigger::Mention, &format!("<@{user}>"), vec![AutomodAction::Warn]));
+    }
+
+    if let Some(role) = forbidden.role {
+        rules.push(AutomodRule::new(guild_id, "rol-prohibido", AutomodTrigger::Mention, &format!("<@&{role}>"), vec![AutomodAction::Warn]));
+    }
+
+    rules.push(AutomodRule::new(guild_id, "everyone", AutomodTrigger::Mention, "everyone,here", vec![AutomodAction::Delete, AutomodAction::Timeout]));
+    rules.push(AutomodRule::new(guild_id, "spam-enlaces", AutomodTrigger::LinkSpam, "", vec![AutomodAction::Delete, AutomodAction::Timeout]));
+
+    rules
+}
+
+/// Administra las reglas de moderación automática del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral,
+    subcommands("automod_enable", "automod_add", "automod_exempt", "automod_remove", "automod_list"),
+    subcommand_required
+)]
+pub async fn automod(_: Context<'_>) -> CommandResult {
+    Ok(())
+}
+
+/// Activa o desactiva las reglas de moderación automática y crea las reglas por defecto
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "enable",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn automod_enable(
+    ctx: Context<'_>,
+    #[description = "Activar las reglas de moderación automática"] enabled: bool,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let automod = Automod { enabled: Some(enabled.to_string()) };
+    let existing_data = GuildData::verify_data(guild_id).await?;
+
+    let mut message = if enabled {
+        "Las reglas de moderación automática han sido activadas".to_string()
+    } else {
+        "Las reglas de moderación automática han sido desactivadas".to_string()
+    };
+
+    if enabled && AutomodRule::get_rules(guild_id).await?.is_empty() {
+        let rules = default_rules(guild_id, existing_data.as_ref());
+        for rule in &rules {
+            rule.save_to_db().await?;
+        }
+
+        write!(message, "\nSe han creado {} reglas por defecto a partir de la configuración actual", rules.len())?;
+    }
+
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .automod(automod);
+
+        data.save_to_db().await?;
+        ctx.say(message).await?;
+
+        return Ok(())
+    }
+
+    automod.update_field_in_db("automod.enabled", &enabled.to_string(), &guild_id.to_string()).await?;
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Añade una regla de moderación automática
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "add",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn automod_add(
+    ctx: Context<'_>,
+    #[description = "Nombre de la regla"] name: String,
+    #[description = "Disparador de la regla"] trigger: AutomodTrigger,
+    #[description = "Menciones, expresión regular, dominios o tipos de archivo separados por comas"] value: Option<String>,
+    #[description = "Acciones separadas por comas: delete, warn, timeout, role, log"] actions: String,
+    #[description = "Aplicar la regla solo en este canal"] channel: Option<ChannelId>,
+    #[description = "Aplicar la regla solo a los miembros con este rol"] condition_role: Option<Role>,
+    #[description = "Aplicar la regla solo a las cuentas con menos horas de antigüedad"]
+    #[min = 1]
+    #[max = 8760]
+    max_account_age: Option<u32>,
+    #[description = "Duración del silencio en segundos (por defecto: la del servidor)"]
+    #[min = 60]
+    #[max = 2_419_200]
+    timeout: Option<u32>,
+    #[description = "Rol que se asigna con la acción role"] role: Option<Role>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let value = value.unwrap_or_default();
+    let parsed = actions.split(',').map(AutomodAction::parse).collect::<Option<Vec<_>>>();
+
+    let Some(actions) = parsed.filter(|actions| !actions.is_empty()) else {
+        ctx.say("Las acciones deben ser una lista de: delete, warn, timeout, role, log").await?;
+        return Ok(())
+    };
+
+    if actions.contains(&AutomodAction::Role) && role.is_none() {
+        ctx.say("Debes indicar el rol que se asigna con la acción role").await?;
+        return Ok(())
+    }
+
+    if trigger == AutomodTrigger::Mention && value.trim().is_empty() {
+        ctx.say("Debes indicar a quién no se puede mencionar").await?;
+        return Ok(())
+    }
+
+    if trigger == AutomodTrigger::Regex {
+        if let Err(why) = compile_rule_regex(&value) {
+            ctx.say(format!("La expresión regular no es válida:\n```\n{why}\n```")).await?;
+            return Ok(())
+        }
+    }
+
+    let mut rule = AutomodRule::new(guild_id, &name, trigger, &value, actions);
+    rule.channels.extend(channel);
+    rule.roles.extend(condition_role.map(|role| role.id));
+    rule.max_account_age = max_account_age.map(i64::from);
+    rule.timeout = timeout.map(i64::from);
+    rule.role = role.map(|role| role.id);
+
+    // Se elimina antes de guardarla para no duplicar la regla
+    AutomodRule::remove_rule(guild_id, &name).await?;
+    rule.save_to_db().await?;
+    ctx.say(format!("Se ha añadido la regla {}", rule.describe())).await?;
+
+    Ok(())
+}
+
+/// Exime un canal o un rol de una regla de moderación automática
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "exempt",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn automod_exempt(
+    ctx: Context<'_>,
+    #[description = "Regla a modificar"]
+    #[autocomplete = "automod_rules"]
+    name: String,
+    #[description = "The channel to exempt"] channel: Option<ChannelId>,
+    #[description = "The role to exempt"] role: Option<Role>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let rules = AutomodRule::get_rules(guild_id).await?;
+    let Some(mut rule) = rules.into_iter().find(|rule| rule.name == name) else {
+        ctx.say(format!("No existe la regla `{name}`")).await?;
+        return Ok(())
+    };
+
+    if channel.is_none() && role.is_none() {
+        ctx.say("Debes indicar un canal o un rol").await?;
+        return Ok(())
+    }
+
+    if let Some(channel) = channel.filter(|channel| !rule.exempt_channels.contains(channel)) {
+        rule.exempt_channels.push(channel);
+    }
+
+    if let Some(role) = role.filter(|role| !rule.exempt_roles.contains(&role.id)) {
+        rule.exempt_roles.push(role.id);
+    }
+
+    AutomodRule::remove_rule(guild_id, &name).await?;
+    rule.save_to_db().await?;
+    ctx.say(format!("Se han actualizado las exenciones de la regla `{name}`")).await?;
+
+    Ok(())
+}
+
+/// Elimina una regla de moderación automática
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "remove",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn automod_remove(
+    ctx: Context<'_>,
+    #[description = "Regla a eliminar"]
+    #[autocomplete = "automod_rules"]
+    name: String,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    AutomodRule::remove_rule(guild_id, &name).await?;
+    ctx.say(format!("Se ha eliminado la regla `{name}`")).await?;
+
+    Ok(())
+}
+
+/// Muestra las reglas de moderación automática
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "list",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn automod_list(ctx: Context<'_>) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let rules = AutomodRule::get_rules(guild_id).await?;
+
+    if rules.is_empty() {
+        ctx.say("No hay reglas de moderación automática").await?;
+        return Ok(())
+    }
+
+    let mut description = "**Reglas de moderación automática:**\n".to_string();
+    for rule in &rules {
+        writeln!(description, "- {}", rule.describe())?;
+        if !rule.channels.is_empty() || !rule.roles.is_empty() || rule.max_account_age.is_some() {
+            let channels = rule.channels.iter().map(|channel| format!("<#{channel}>")).collect::<Vec<_>>().join(" ");
+            let roles = rule.roles.iter().map(|role| format!("<@&{role}>")).collect::<Vec<_>>().join(" ");
+            let age = rule.max_account_age.map_or_else(String::new, |hours| format!(" cuentas de menos de {hours}h"));
+            writeln!(description, "  - Condiciones: {channels} {roles}{age}")?;
+        }
+
+        if !rule.exempt_channels.is_empty() || !rule.exempt_roles.is_empty() {
+            let channels = rule.exempt_channels.iter().map(|channel| format!("<#{channel}>")).collect::<Vec<_>>().join(" ");
+            let roles = rule.exempt_roles.iter().map(|role| format!("<@&{role}>")).collect::<Vec<_>>().join(" ");
+            writeln!(description, "  - Exenciones: {channels} {roles}")?;
+        }
+    }
+
+    ctx.say(description).await?;
+
+    Ok(())
+}
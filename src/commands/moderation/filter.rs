@@ -0,0 +1,258 @@
+use std::fmt::Write;
+
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+use serenity::all::GuildId;
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::handlers::misc::native_automod::resync_native_automod;
+use crate::handlers::misc::word_filter::{compile_rule_regex, invalidate_regex_cache, normalize_text, rule_matches, FilterText};
+use crate::utils::autocomplete::filter_rules;
+use crate::utils::{CommandResult, Context};
+
+/// # Tipo de patrón de una regla del filtro
+///
+/// - `Literal`: Palabra o frase completa, comparada después de normalizar el texto
+/// - `Regex`: Expresión regular, comparada con el texto normalizado
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum FilterKind {
+    #[name = "Palabra"]
+    Literal,
+    #[name = "Expresión regular"]
+    Regex,
+}
+
+/// # Acción a tomar cuando un mensaje coincide con una regla
+///
+/// - Están ordenadas de menor a mayor severidad
+/// - `Log`: Solo se registra en el canal de Logs
+/// - `Delete`: Se borra el mensaje
+/// - `Warn`: Se borra el mensaje y se suma una advertencia
+/// - `Timeout`: Se borra el mensaje y se silencia al autor
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, poise::ChoiceParameter)]
+pub enum FilterAction {
+    #[name = "Solo registrar"]
+    Log,
+    #[name = "Borrar"]
+    Delete,
+    #[name = "Advertir"]
+    Warn,
+    #[name = "Silenciar"]
+    Timeout,
+}
+
+impl FilterAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Log => "log",
+            Self::Delete => "delete",
+            Self::Warn => "warn",
+            Self::Timeout => "timeout",
+        }
+    }
+}
+
+impl From<&str> for FilterAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "log" => Self::Log,
+            "warn" => Self::Warn,
+            "timeout" => Self::Timeout,
+            _ => Self::Delete,
+        }
+    }
+}
+
+/// # Regla del filtro de palabras de un servidor
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FilterRule {
+    pub guild_id: GuildId,
+    pub pattern: String,
+    pub kind: FilterKind,
+    pub action: FilterAction,
+}
+
+impl FilterRule {
+    pub const fn new(guild_id: GuildId, pattern: String, kind: FilterKind, action: FilterAction) -> Self {
+        Self { guild_id, pattern, kind, action }
+    }
+
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("filter_rule")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_rules(guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM filter_rule WHERE guild_id = $guild_id";
+        let rules: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .await?
+            .take(0)?;
+
+        Ok(rules)
+    }
+
+    pub async fn remove_rule(guild_id: GuildId, pattern: &str) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE filter_rule WHERE guild_id = $guild_id AND pattern = $pattern";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("pattern", pattern))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Administra el filtro de palabras del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral,
+    subcommands("filter_add", "filter_remove", "filter_list", "filter_test"),
+    subcommand_required
+)]
+pub async fn filter(_: Context<'_>) -> CommandResult {
+    Ok(())
+}
+
+/// Añade una palabra o expresión regular al filtro de palabras
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "add",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn filter_add(
+    ctx: Context<'_>,
+    #[description = "Palabra o expresión regular a filtrar"] pattern: String,
+    #[description = "Tipo de patrón (por defecto: palabra)"] kind: Option<FilterKind>,
+    #[description = "Acción a tomar (por defecto: borrar)"] action: Option<FilterAction>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let kind = kind.unwrap_or(FilterKind::Literal);
+    let action = action.unwrap_or(FilterAction::Delete);
+
+    if kind == FilterKind::Literal && normalize_text(&pattern).is_empty() {
+        ctx.say("La palabra no puede estar vacía").await?;
+        return Ok(())
+    }
+
+    if kind == FilterKind::Regex {
+        if let Err(why) = compile_rule_regex(&pattern) {
+            ctx.say(format!("La expresión regular no es válida:\n```\n{why}\n```")).await?;
+            return Ok(())
+        }
+    }
+
+    // Se elimina antes de guardarla para no duplicar la regla
+    FilterRule::remove_rule(guild_id, &pattern).await?;
+    FilterRule::new(guild_id, pattern.clone(), kind, action).save_to_db().await?;
+    invalidate_regex_cache(guild_id);
+    resync_native_automod(&ctx.serenity_context().http, guild_id).await;
+    ctx.say(format!("Se ha añadido la regla `{pattern}` ({}) con la acción **{}**", kind.name(), action.name())).await?;
+
+    Ok(())
+}
+
+/// Elimina una regla del filtro de palabras
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "remove",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn filter_remove(
+    ctx: Context<'_>,
+    #[description = "Regla a eliminar"]
+    #[autocomplete = "filter_rules"]
+    pattern: String,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    FilterRule::remove_rule(guild_id, &pattern).await?;
+    invalidate_regex_cache(guild_id);
+    resync_native_automod(&ctx.serenity_context().http, guild_id).await;
+    ctx.say(format!("Se ha eliminado la regla `{pattern}`")).await?;
+
+    Ok(())
+}
+
+/// Muestra las reglas del filtro de palabras
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "list",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn filter_list(ctx: Context<'_>) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let rules = FilterRule::get_rules(guild_id).await?;
+
+    if rules.is_empty() {
+        ctx.say("No hay reglas en el filtro de palabras").await?;
+        return Ok(())
+    }
+
+    let mut description = "**Reglas del filtro de palabras:**\n".to_string();
+    for rule in &rules {
+        writeln!(description, "- `{}` ({}): {}", rule.pattern, rule.kind.name(), rule.action.name())?;
+    }
+
+    ctx.say(description).await?;
+
+    Ok(())
+}
+
+/// Prueba un texto con las reglas del filtro de palabras sin aplicar ninguna acción
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "test",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn filter_test(
+    ctx: Context<'_>,
+    #[description = "Texto a probar"] text: String,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let rules = FilterRule::get_rules(guild_id).await?;
+    let text = FilterText::new(&text);
+
+    let mut description = format!("**Texto normalizado:** `{}`\n", text.normalized);
+    let matches = rules.iter().filter(|rule| rule_matches(rule, &text)).collect::<Vec<_>>();
+    if matches.is_empty() {
+        description.push_str("No coincide con ninguna regla");
+    } else {
+        description.push_str("**Reglas que coinciden:**\n");
+        for rule in matches {
+            writeln!(description, "- `{}` ({}): {}", rule.pattern, rule.kind.name(), rule.action.name())?;
+        }
+    }
+
+    ctx.say(description).await?;
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::fmt::Write;
+
+use chrono::Utc;
+use serenity::all::UserId;
+
+use crate::commands::moderation::setters::set_availability::{AvailabilityWindow, is_available_at, next_change};
+use crate::commands::moderation::setters::set_forbidden_exception::ForbiddenException;
+use crate::utils::{CommandResult, Context};
+
+/// Muestra las ventanas de disponibilidad de un usuario, su estado actual y el próximo cambio
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    category = "Moderator",
+    ephemeral
+)]
+pub async fn get_availability(
+    ctx: Context<'_>,
+    #[description = "The user id to get the availability windows"] user: Option<UserId>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let user_id = user.unwrap_or(ctx.author().id);
+    let windows = AvailabilityWindow::get_windows(user_id, guild_id).await?;
+    let username = user_id.to_user(ctx.http()).await?.name;
+
+    if windows.is_empty() {
+        ctx.say(format!("El usuario **{username}** no tiene ventanas de disponibilidad")).await?;
+        return Ok(())
+    }
+
+    let now = Utc::now();
+    let is_available = is_available_at(&windows, now);
+    let status = if is_available { "Disponible (se permite el uso de @)" } else { "No disponible" };
+    let mut description = format!("Ventanas de disponibilidad de **{username}**:\n");
+    for window in &windows {
+        writeln!(description, "- {}", window.describe())?;
+    }
+
+    writeln!(description, "\nEstado actual: **{status}**")?;
+    if let Some(change) = next_change(&windows, now) {
+        let timestamp = change.timestamp();
+        writeln!(description, "Próximo cambio: <t:{timestamp}:F> (<t:{timestamp}:R>)")?;
+    }
+
+    if ForbiddenException::have_exception(user_id).await?.unwrap_or_default() {
+        writeln!(description, "Nota: El usuario tiene una excepción manual activa")?;
+    }
+
+    ctx.say(description).await?;
+
+    Ok(())
+}
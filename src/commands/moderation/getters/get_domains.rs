@@ -0,0 +1,42 @@
+use std::fmt::Write;
+
+use crate::commands::moderation::setters::set_domain::{DomainList, LinkDomain};
+use crate::utils::{CommandResult, Context};
+
+/// Muestra los dominios permitidos y bloqueados del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    category = "Moderator",
+    ephemeral
+)]
+pub async fn get_domains(ctx: Context<'_>) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let domains = LinkDomain::get_domains(guild_id).await?;
+
+    if domains.is_empty() {
+        ctx.say("No se han establecido dominios permitidos ni bloqueados").await?;
+        return Ok(())
+    }
+
+    let mut description = String::new();
+    for (list, title) in [(DomainList::Allow, "Dominios permitidos"), (DomainList::Block, "Dominios bloqueados")] {
+        let mut domains = domains
+            .iter()
+            .filter(|domain| domain.list() == list)
+            .map(|domain| domain.domain.as_str())
+            .collect::<Vec<_>>();
+
+        if domains.is_empty() { continue }
+        domains.sort_unstable();
+        writeln!(description, "**{title}:**")?;
+        for domain in domains {
+            writeln!(description, "- `{domain}`")?;
+        }
+    }
+
+    ctx.say(description).await?;
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::fmt::Write;
+
+use crate::commands::moderation::setters::set_invite_filter::InvitePartner;
+use crate::utils::{CommandResult, Context};
+
+/// Muestra los servidores asociados cuyas invitaciones están permitidas
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    category = "Moderator",
+    ephemeral
+)]
+pub async fn get_invite_partners(ctx: Context<'_>) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let partners = InvitePartner::get_partners(guild_id).await?;
+
+    if partners.is_empty() {
+        ctx.say("No hay servidores asociados. Solo se permiten las invitaciones a este servidor").await?;
+        return Ok(())
+    }
+
+    let mut description = "**Servidores asociados:**\n".to_string();
+    for partner in &partners {
+        writeln!(description, "- `{}`", partner.partner_id)?;
+    }
+
+    ctx.say(description).await?;
+
+    Ok(())
+}
@@ -6,4 +6,8 @@ pub mod get_admins;
 pub mod get_forbidden_exception;
 pub mod get_welcome_channel;
 pub mod get_ooc_channel;
-pub mod get_exception_channel;
\ No newline at end of file
+pub mod get_exception_channel;
+pub mod get_availability;
+pub mod preview_message;
+pub mod get_domains;
+pub mod get_invite_partners;
\ No newline at end of file
@@ -0,0 +1,77 @@
+use crate::handlers::goodbye::{time_in_server, DEFAULT_GOODBYE_MESSAGE};
+use crate::handlers::misc::forbidden_mentions::{DEFAULT_TIME_OUT_MESSAGE, DEFAULT_WARN_MESSAGE};
+use crate::handlers::misc::warns::MAX_WARNS;
+use crate::handlers::welcome::LEGACY_WELCOME_PREFIX;
+use crate::utils::{CommandResult, Context, Warns};
+use crate::utils::config::GuildData;
+use crate::utils::template::{available_placeholders, format_duration, upgrade_legacy, validate_template, TemplateContext};
+
+/// Mensaje personalizado que se puede previsualizar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum MessageKind {
+    #[name = "Advertencia"]
+    Warn,
+    #[name = "Silencio"]
+    TimeOut,
+    #[name = "Bienvenida"]
+    Welcome,
+    #[name = "Despedida"]
+    Goodbye,
+}
+
+/// Muestra cómo se verá un mensaje personalizado usando al autor del comando como ejemplo
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn preview_message(
+    ctx: Context<'_>,
+    #[description = "El mensaje a previsualizar"] kind: MessageKind,
+    #[description = "Una plantilla sin guardar para previsualizar (opcional)"] template: Option<String>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let guild_data = GuildData::verify_data(guild_id).await?.unwrap_or_default();
+
+    let template = match template {
+        Some(template) => {
+            if let Err(why) = validate_template(&template) {
+                ctx.say(format!("El mensaje no es válido: {why}")).await?;
+                return Ok(())
+            }
+
+            template
+        }
+        None => match kind {
+            MessageKind::Warn => guild_data.messages.warn
+                .map_or_else(|| DEFAULT_WARN_MESSAGE.to_string(), |warn| upgrade_legacy(&warn, "{user} ")),
+            MessageKind::TimeOut => guild_data.messages.time_out
+                .map_or_else(|| DEFAULT_TIME_OUT_MESSAGE.to_string(), |time_out| upgrade_legacy(&time_out, "{mention} ")),
+            MessageKind::Welcome => guild_data.messages.welcome
+                .map_or_else(|| LEGACY_WELCOME_PREFIX.to_string(), |welcome| upgrade_legacy(&welcome, LEGACY_WELCOME_PREFIX)),
+            MessageKind::Goodbye => guild_data.goodbye.message
+                .unwrap_or_else(|| DEFAULT_GOODBYE_MESSAGE.to_string()),
+        },
+    };
+
+    let member = guild_id.member(ctx.serenity_context(), ctx.author().id).await?;
+    let warns = Warns::new(ctx.author().id).get_warns().await?.map_or(1, |warns| warns.warns.max(1));
+    let duration = guild_data.time_out.time
+        .and_then(|time| time.parse::<i64>().ok())
+        .map_or_else(|| "(tiempo sin establecer)".to_string(), format_duration);
+
+    let preview = TemplateContext::for_member(ctx.serenity_context(), &member)
+        .channel_id(ctx.channel_id())
+        .warns(warns)
+        .max_warns(MAX_WARNS)
+        .duration(duration)
+        .time_in_server(time_in_server(member.joined_at))
+        .render(&template);
+
+    ctx.say(format!("**Vista previa:**\n{preview}\n\n-# Variables disponibles: {}", available_placeholders())).await?;
+
+    Ok(())
+}
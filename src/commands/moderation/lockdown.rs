@@ -0,0 +1,193 @@
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, ChannelType, GuildId, PermissionOverwrite, PermissionOverwriteType, Permissions};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::log_handle;
+use crate::handlers::error::{is_discord_error, UNKNOWN_CHANNEL};
+use crate::utils::{CommandResult, Context};
+
+/// # Permisos que se quitan a `@everyone` durante el bloqueo
+fn locked_permissions() -> Permissions {
+    Permissions::SEND_MESSAGES
+        | Permissions::SEND_MESSAGES_IN_THREADS
+        | Permissions::CREATE_PUBLIC_THREADS
+        | Permissions::ADD_REACTIONS
+}
+
+/// # Permisos de `@everyone` en un canal antes del bloqueo
+///
+/// - `existed` indica si el canal tenía permisos propios para `@everyone`
+/// - Si no los tenía, al terminar el bloqueo se eliminan en lugar de restaurarse
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LockdownSnapshot {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub allow: u64,
+    pub deny: u64,
+    pub existed: bool,
+}
+
+impl LockdownSnapshot {
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("lockdown_snapshot")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_snapshots(guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM lockdown_snapshot WHERE guild_id = $guild_id";
+        let snapshots: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .await?
+            .take(0)?;
+
+        Ok(snapshots)
+    }
+
+    pub async fn remove_snapshot(guild_id: GuildId, channel_id: ChannelId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE lockdown_snapshot WHERE guild_id = $guild_id AND channel_id = $channel_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("channel_id", channel_id))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Bloquea o desbloquea todos los canales de texto del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MANAGE_CHANNELS",
+    guild_only,
+    ephemeral,
+    subcommands("lockdown_start", "lockdown_end"),
+    subcommand_required
+)]
+pub async fn lockdown(_: Context<'_>) -> CommandResult {
+    Ok(())
+}
+
+/// Bloquea los canales de texto: `@everyone` no podrá enviar mensajes ni reaccionar
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "start",
+    category = "Moderator",
+    required_permissions = "MANAGE_CHANNELS",
+    guild_only,
+    ephemeral
+)]
+pub async fn lockdown_start(ctx: Context<'_>) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    if !LockdownSnapshot::get_snapshots(guild_id).await?.is_empty() {
+        ctx.say("El servidor ya está bloqueado. Usa `/lockdown end` para desbloquearlo").await?;
+        return Ok(())
+    }
+
+    ctx.defer_ephemeral().await?;
+    let everyone = PermissionOverwriteType::Role(guild_id.everyone_role());
+    let channels = guild_id.channels(ctx.http()).await?;
+    let mut locked = 0;
+    for channel in channels.values().filter(|channel| matches!(channel.kind, ChannelType::Text | ChannelType::News | ChannelType::Forum)) {
+        let current = channel.permission_overwrites
+            .iter()
+            .find(|overwrite| overwrite.kind == everyone);
+
+        // Se guardan los permisos antes de cambiarlos para poder restaurarlos aunque el Bot se reinicie
+        let snapshot = LockdownSnapshot {
+            guild_id,
+            channel_id: channel.id,
+            allow: current.map_or(0, |overwrite| overwrite.allow.bits()),
+            deny: current.map_or(0, |overwrite| overwrite.deny.bits()),
+            existed: current.is_some(),
+        };
+        snapshot.save_to_db().await?;
+
+        let overwrite = PermissionOverwrite {
+            allow: Permissions::from_bits_truncate(snapshot.allow) - locked_permissions(),
+            deny: Permissions::from_bits_truncate(snapshot.deny) | locked_permissions(),
+            kind: everyone,
+        };
+
+        match channel.id.create_permission(ctx.http(), overwrite).await {
+            Ok(()) => locked += 1,
+            Err(why) => log_handle!("No se pudo bloquear el canal {}: {why}", channel.id),
+        }
+    }
+
+    ctx.say(format!("Se han bloqueado {locked} canales. Usa `/lockdown end` para restaurar los permisos")).await?;
+
+    Ok(())
+}
+
+/// Desbloquea los canales y restaura los permisos que tenían antes del bloqueo
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "end",
+    category = "Moderator",
+    required_permissions = "MANAGE_CHANNELS",
+    guild_only,
+    ephemeral
+)]
+pub async fn lockdown_end(ctx: Context<'_>) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let snapshots = LockdownSnapshot::get_snapshots(guild_id).await?;
+    if snapshots.is_empty() {
+        ctx.say("El servidor no está bloqueado").await?;
+        return Ok(())
+    }
+
+    ctx.defer_ephemeral().await?;
+    let everyone = PermissionOverwriteType::Role(guild_id.everyone_role());
+    let mut restored = 0;
+    let mut failed = Vec::new();
+    for snapshot in &snapshots {
+        let result = if snapshot.existed {
+            let overwrite = PermissionOverwrite {
+                allow: Permissions::from_bits_truncate(snapshot.allow),
+                deny: Permissions::from_bits_truncate(snapshot.deny),
+                kind: everyone,
+            };
+            snapshot.channel_id.create_permission(ctx.http(), overwrite).await
+        } else {
+            snapshot.channel_id.delete_permission(ctx.http(), everyone).await
+        };
+
+        // Solo se borran los permisos guardados de los canales restaurados o eliminados,
+        // para poder reintentar el resto con otro `/lockdown end`
+        match result {
+            Ok(()) => restored += 1,
+            Err(why) if is_discord_error(&why, UNKNOWN_CHANNEL) => {}
+            Err(why) => {
+                log_handle!("No se pudieron restaurar los permisos del canal {}: {why}", snapshot.channel_id);
+                failed.push(format!("<#{}>", snapshot.channel_id));
+                continue
+            }
+        }
+
+        LockdownSnapshot::remove_snapshot(guild_id, snapshot.channel_id).await?;
+    }
+
+    let mut response = format!("Se han restaurado los permisos de {restored} canales");
+    if !failed.is_empty() {
+        write!(response, "\nNo se pudieron restaurar: {}. Usa `/lockdown end` para reintentarlo", failed.join(", "))?;
+    }
+
+    ctx.say(response).await?;
+
+    Ok(())
+}
@@ -1,2 +1,7 @@
 pub mod getters;
-pub mod setters;
\ No newline at end of file
+pub mod setters;
+pub mod lockdown;
+pub mod filter;
+pub mod automod;
+pub mod welcome;
+pub mod auto_role;
\ No newline at end of file
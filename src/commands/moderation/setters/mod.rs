@@ -9,4 +9,23 @@ pub mod set_forbidden_exception;
 pub mod set_welcome_channel;
 pub mod set_welcome_message;
 pub mod set_ooc_channel;
-pub mod set_exception_channel;
\ No newline at end of file
+pub mod set_exception_channel;
+pub mod set_reply_ping_policy;
+pub mod set_availability;
+pub mod set_exception_timer;
+pub mod set_ghost_ping;
+pub mod set_mass_mention;
+pub mod set_warn_embed;
+pub mod set_dry_run;
+pub mod set_spam_thresholds;
+pub mod set_domain;
+pub mod set_flood;
+pub mod set_invite_filter;
+pub mod set_image_spam;
+pub mod set_raid;
+pub mod set_account_age;
+pub mod set_text_filter;
+pub mod set_quarantine;
+pub mod set_native_automod;
+pub mod set_goodbye;
+pub mod set_captcha;
@@ -0,0 +1,110 @@
+use poise::ChoiceParameter;
+use serenity::all::Role;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{AccountAge, GuildData};
+
+/// # Acción a tomar con las cuentas demasiado nuevas
+///
+/// - `Quarantine`: Se asigna el rol de cuarentena configurado
+/// - `Dm`: Se envía un mensaje privado explicando el motivo
+/// - `Kick`: Se envía el mensaje privado y se expulsa al miembro
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum AccountAgeAction {
+    #[name = "Rol de cuarentena"]
+    Quarantine,
+    #[name = "Mensaje privado"]
+    Dm,
+    #[name = "Expulsar"]
+    Kick,
+}
+
+impl AccountAgeAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Quarantine => "quarantine",
+            Self::Dm => "dm",
+            Self::Kick => "kick",
+        }
+    }
+}
+
+impl From<&str> for AccountAgeAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "quarantine" => Self::Quarantine,
+            "kick" => Self::Kick,
+            _ => Self::Dm,
+        }
+    }
+}
+
+/// Establece la antigüedad mínima de las cuentas que entran al servidor (0 para desactivarla)
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_account_age(
+    ctx: Context<'_>,
+    #[description = "Horas mínimas de antigüedad de la cuenta (0 para desactivar)"]
+    #[max = 8760]
+    min_hours: u32,
+    #[description = "Acción a tomar con las cuentas demasiado nuevas"] action: Option<AccountAgeAction>,
+    #[description = "Rol de cuarentena para las cuentas demasiado nuevas"] role: Option<Role>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    let has_role = role.is_some() || existing_data
+        .as_ref()
+        .is_some_and(|data| data.account_age.role.is_some());
+
+    if action == Some(AccountAgeAction::Quarantine) && !has_role {
+        ctx.say("Debes indicar un rol de cuarentena para usar esta acción").await?;
+        return Ok(())
+    }
+
+    let account_age = AccountAge {
+        min_hours: Some(min_hours.to_string()),
+        action: action.map(|action| action.as_str().to_string()),
+        role: role.as_ref().map(|role| role.id.to_string()),
+    };
+
+    let message = if min_hours == 0 {
+        "La antigüedad mínima de las cuentas ha sido desactivada".to_string()
+    } else {
+        let action = action.map_or_else(String::new, |action| format!(" con la acción **{}**", action.name()));
+        format!("La antigüedad mínima de las cuentas es de **{min_hours} horas**{action}")
+    };
+
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .account_age(account_age);
+
+        data.save_to_db().await?;
+        ctx.say(message).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("account_age.min_hours", &account_age.min_hours),
+        ("account_age.action", &account_age.action),
+        ("account_age.role", &account_age.role),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        account_age.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
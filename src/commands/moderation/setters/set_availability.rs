@@ -0,0 +1,276 @@
+use chrono::{DateTime, Datelike, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, Permissions, UserId};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::handlers::misc::native_automod::resync_native_automod;
+use crate::utils::{CommandResult, Context};
+
+const DAY_NAMES: [&str; 7] = ["Lunes", "Martes", "Miércoles", "Jueves", "Viernes", "Sábado", "Domingo"];
+
+/// # Ventana semanal de disponibilidad del usuario prohibido
+///
+/// - Dentro de la ventana se permite mencionar al usuario con `@`
+/// - `days` almacena los días de la semana empezando por el lunes (`0`)
+/// - `start` y `end` se almacenan en formato `HH:MM` en la zona horaria `timezone`
+/// - Si `end` es menor o igual a `start`, la ventana termina al día siguiente
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AvailabilityWindow {
+    pub user_id: UserId,
+    pub guild_id: GuildId,
+    pub days: Vec<u8>,
+    pub start: String,
+    pub end: String,
+    pub timezone: String,
+}
+
+impl AvailabilityWindow {
+    pub fn new(user_id: UserId, guild_id: GuildId, days: Vec<u8>, start: String, end: String, timezone: String) -> Self {
+        Self {
+            user_id,
+            guild_id,
+            days,
+            start,
+            end,
+            timezone,
+        }
+    }
+
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("availability_window")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_windows(user_id: UserId, guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM availability_window WHERE guild_id = $guild_id AND user_id = $user_id";
+        let windows: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?
+            .take(0)?;
+
+        Ok(windows)
+    }
+
+    pub async fn clear_windows(user_id: UserId, guild_id: GuildId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE availability_window WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?;
+
+        Ok(())
+    }
+
+    /// Verifica si el usuario se encuentra dentro de alguna de sus ventanas de disponibilidad
+    pub async fn is_available(user_id: UserId, guild_id: GuildId) -> SurrealResult<bool> {
+        let windows = Self::get_windows(user_id, guild_id).await?;
+        Ok(is_available_at(&windows, Utc::now()))
+    }
+
+    /// # Obtiene los intervalos de la ventana en UTC
+    ///
+    /// - Se calculan los intervalos desde el día anterior hasta una semana después de `now`
+    ///     para cubrir las ventanas que empiezan un día y terminan al siguiente
+    fn occurrences(&self, now: DateTime<Utc>) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let (Ok(tz), Some(start), Some(end)) = (self.timezone.parse::<Tz>(), parse_time(&self.start), parse_time(&self.end)) else {
+            return Vec::new()
+        };
+
+        let today = now.with_timezone(&tz).date_naive();
+        (-1..=8)
+            .filter_map(|offset| today.checked_add_signed(Duration::try_days(offset)?))
+            .filter(|date| self.days.contains(&(date.weekday().num_days_from_monday() as u8)))
+            .filter_map(|date| {
+                let end_date = if end <= start { date.succ_opt()? } else { date };
+                let start = tz.from_local_datetime(&date.and_time(start)).earliest()?;
+                let end = tz.from_local_datetime(&end_date.and_time(end)).earliest()?;
+                Some((start.with_timezone(&Utc), end.with_timezone(&Utc)))
+            })
+            .collect()
+    }
+
+    /// Describe la ventana en un formato legible, por ejemplo: `Lunes, Martes 18:00-22:00 (America/Lima)`
+    pub fn describe(&self) -> String {
+        let days = self.days
+            .iter()
+            .filter_map(|day| DAY_NAMES.get(*day as usize).copied())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{days} {}-{} ({})", self.start, self.end, self.timezone)
+    }
+}
+
+/// Verifica si `now` se encuentra dentro de alguna de las ventanas
+pub fn is_available_at(windows: &[AvailabilityWindow], now: DateTime<Utc>) -> bool {
+    windows
+        .iter()
+        .flat_map(|window| window.occurrences(now))
+        .any(|(start, end)| start <= now && now < end)
+}
+
+/// # Obtiene el próximo momento en el que cambia la disponibilidad
+///
+/// - Recorre en orden los inicios y finales de las ventanas posteriores a `now`
+/// - Devuelve el primero en el que el estado es distinto al estado actual
+///     (las ventanas que se solapan no generan un cambio)
+pub fn next_change(windows: &[AvailabilityWindow], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let current_state = is_available_at(windows, now);
+    let mut boundaries = windows
+        .iter()
+        .flat_map(|window| window.occurrences(now))
+        .flat_map(|(start, end)| [start, end])
+        .filter(|boundary| *boundary > now)
+        .collect::<Vec<_>>();
+
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    boundaries
+        .into_iter()
+        .find(|boundary| is_available_at(windows, *boundary) != current_state)
+}
+
+/// Convierte un texto `HH:MM` en una hora
+pub fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M").ok()
+}
+
+/// # Convierte un texto en una lista de días de la semana
+///
+/// - Acepta días separados por comas y rangos: `lun-vie`, `sab,dom`, `mon-fri`
+/// - Acepta los atajos `laborables`, `fines` y `todos` (también en inglés)
+pub fn parse_days(days: &str) -> Option<Vec<u8>> {
+    let mut result = Vec::new();
+    for token in days.split(',').map(str::trim).filter(|token| !token.is_empty()) {
+        match token.to_lowercase().as_str() {
+            "laborables" | "weekdays" => result.extend(0..=4),
+            "fines" | "weekend" => result.extend(5..=6),
+            "todos" | "all" => result.extend(0..=6),
+            token => {
+                if let Some((from, to)) = token.split_once('-') {
+                    let (from, to) = (parse_day(from)?, parse_day(to)?);
+                    // Los rangos pueden cruzar el domingo, por ejemplo `vie-lun`
+                    let mut day = from;
+                    loop {
+                        result.push(day);
+                        if day == to { break }
+                        day = (day + 1) % 7;
+                    }
+                } else {
+                    result.push(parse_day(token)?);
+                }
+            }
+        }
+    }
+
+    result.sort_unstable();
+    result.dedup();
+    if result.is_empty() { None } else { Some(result) }
+}
+
+fn parse_day(day: &str) -> Option<u8> {
+    let prefix = day.trim().chars().take(3).collect::<String>().to_lowercase();
+    match prefix.as_str() {
+        "lun" | "mon" => Some(0),
+        "mar" | "tue" => Some(1),
+        "mie" | "mié" | "wed" => Some(2),
+        "jue" | "thu" => Some(3),
+        "vie" | "fri" => Some(4),
+        "sab" | "sáb" | "sat" => Some(5),
+        "dom" | "sun" => Some(6),
+        _ => None,
+    }
+}
+
+/// Añade una ventana semanal en la que el usuario prohibido permite que lo mencionen con @
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    category = "Moderator",
+    ephemeral
+)]
+pub async fn set_availability(
+    ctx: Context<'_>,
+    #[description = "Días de la semana (ej: lun-vie, sab,dom, laborables)"] days: String,
+    #[description = "Hora de inicio en formato HH:MM (ej: 18:00)"] start: String,
+    #[description = "Hora de fin en formato HH:MM (ej: 22:00)"] end: String,
+    #[description = "Zona horaria (ej: America/Mexico_City)"] timezone: String,
+    #[description = "The user id to set the availability window"] user: Option<UserId>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap(); // SAFETY: Al estar el parámetro guild_only, la función solo se ejecutará en un servidor
+    let user_id = user.unwrap_or(ctx.author().id);
+
+    if user_id != ctx.author().id {
+        let member = guild_id.member(ctx.serenity_context(), ctx.author().id).await?;
+        let member_permissions = member.permissions(ctx.serenity_context())?;
+        if !member_permissions.contains(Permissions::ADMINISTRATOR) {
+            poise::say_reply(ctx, "Debes ser administrador para cambiar la disponibilidad de otros usuarios").await?;
+            return Ok(());
+        }
+    }
+
+    let Some(days) = parse_days(&days) else {
+        poise::say_reply(ctx, "Días no válidos. Usa por ejemplo: `lun-vie`, `sab,dom` o `laborables`").await?;
+        return Ok(())
+    };
+
+    if parse_time(&start).is_none() || parse_time(&end).is_none() {
+        poise::say_reply(ctx, "Las horas deben tener el formato `HH:MM`, por ejemplo `18:00`").await?;
+        return Ok(())
+    }
+
+    if timezone.parse::<Tz>().is_err() {
+        poise::say_reply(ctx, "Zona horaria no válida. Usa por ejemplo: `America/Mexico_City` o `Europe/Madrid`").await?;
+        return Ok(())
+    }
+
+    let window = AvailabilityWindow::new(user_id, guild_id, days, start.trim().to_string(), end.trim().to_string(), timezone);
+    window.save_to_db().await?;
+    resync_native_automod(&ctx.serenity_context().http, guild_id).await;
+    poise::say_reply(ctx, format!("Ventana de disponibilidad añadida: **{}**", window.describe())).await?;
+
+    Ok(())
+}
+
+/// Elimina todas las ventanas de disponibilidad de un usuario
+#[poise::command(
+    prefix_command,
+    slash_command,
+    guild_only,
+    category = "Moderator",
+    ephemeral
+)]
+pub async fn clear_availability(
+    ctx: Context<'_>,
+    #[description = "The user id to clear the availability windows"] user: Option<UserId>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap(); // SAFETY: Al estar el parámetro guild_only, la función solo se ejecutará en un servidor
+    let user_id = user.unwrap_or(ctx.author().id);
+
+    if user_id != ctx.author().id {
+        let member = guild_id.member(ctx.serenity_context(), ctx.author().id).await?;
+        let member_permissions = member.permissions(ctx.serenity_context())?;
+        if !member_permissions.contains(Permissions::ADMINISTRATOR) {
+            poise::say_reply(ctx, "Debes ser administrador para cambiar la disponibilidad de otros usuarios").await?;
+            return Ok(());
+        }
+    }
+
+    AvailabilityWindow::clear_windows(user_id, guild_id).await?;
+    resync_native_automod(&ctx.serenity_context().http, guild_id).await;
+    poise::say_reply(ctx, "Se han eliminado las ventanas de disponibilidad").await?;
+
+    Ok(())
+}
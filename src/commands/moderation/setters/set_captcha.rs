@@ -0,0 +1,82 @@
+use serenity::all::{ChannelId, Role};
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{Captcha, GuildData};
+
+/// Exige a los miembros nuevos resolver un captcha antes de acceder al servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_captcha(
+    ctx: Context<'_>,
+    #[description = "Activar la verificación con captcha"] enabled: bool,
+    #[description = "Rol de los miembros sin verificar"] role: Option<Role>,
+    #[description = "Rol que se asigna al resolver el captcha"] verified_role: Option<Role>,
+    #[description = "Canal donde se envía el captcha"] channel: Option<ChannelId>,
+    #[description = "Minutos para resolver el captcha antes de ser expulsado (por defecto: 10)"]
+    #[min = 1]
+    #[max = 1440]
+    timeout: Option<u32>,
+    #[description = "Intentos antes de ser expulsado (por defecto: 3)"]
+    #[min = 1]
+    #[max = 10]
+    attempts: Option<u32>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    let current = existing_data.as_ref().map(|data| data.captcha.clone()).unwrap_or_default();
+
+    let has_role = role.is_some() || current.role.is_some();
+    let has_channel = channel.is_some() || current.channel.is_some();
+
+    if enabled && !(has_role && has_channel) {
+        ctx.say("Debes indicar el rol sin verificar y el canal del captcha para activarlo").await?;
+        return Ok(())
+    }
+
+    let captcha = Captcha {
+        enabled: Some(enabled.to_string()),
+        role: role.map(|role| role.id.to_string()),
+        verified_role: verified_role.map(|role| role.id.to_string()),
+        channel: channel.map(|channel| channel.to_string()),
+        timeout: timeout.map(|minutes| (u64::from(minutes) * 60).to_string()),
+        attempts: attempts.map(|attempts| attempts.to_string()),
+    };
+
+    let status = if enabled { "activada" } else { "desactivada" };
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .captcha(captcha);
+
+        data.save_to_db().await?;
+        ctx.say(format!("La verificación con captcha ha sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("captcha.enabled", &captcha.enabled),
+        ("captcha.role", &captcha.role),
+        ("captcha.verified_role", &captcha.verified_role),
+        ("captcha.channel", &captcha.channel),
+        ("captcha.timeout", &captcha.timeout),
+        ("captcha.attempts", &captcha.attempts),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        captcha.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    ctx.say(format!("La verificación con captcha ha sido {status}")).await?;
+
+    Ok(())
+}
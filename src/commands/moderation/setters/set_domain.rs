@@ -0,0 +1,174 @@
+use poise::ChoiceParameter;
+use serde::{Deserialize, Serialize};
+use serenity::all::GuildId;
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+
+/// # Lista a la que pertenece un dominio
+///
+/// - `Allow`: Los enlaces al dominio siempre se permiten
+/// - `Block`: Los enlaces al dominio se borran y se advierte al autor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum DomainList {
+    #[name = "Permitidos"]
+    Allow,
+    #[name = "Bloqueados"]
+    Block,
+}
+
+impl DomainList {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Block => "block",
+        }
+    }
+}
+
+impl From<&str> for DomainList {
+    fn from(value: &str) -> Self {
+        match value {
+            "allow" => Self::Allow,
+            _ => Self::Block,
+        }
+    }
+}
+
+/// # Dominio permitido o bloqueado en un servidor
+///
+/// - `domain` se almacena normalizado (minúsculas y punycode)
+/// - `*.ejemplo.com` coincide solo con los subdominios
+/// - `ejemplo.com` coincide con el dominio y todos sus subdominios
+/// - `*` dentro de una etiqueta funciona como comodín (ej: `discord*.gift`)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LinkDomain {
+    pub guild_id: GuildId,
+    pub domain: String,
+    pub list: String,
+}
+
+impl LinkDomain {
+    pub fn new(guild_id: GuildId, domain: String, list: DomainList) -> Self {
+        Self {
+            guild_id,
+            domain,
+            list: list.as_str().to_string(),
+        }
+    }
+
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("link_domain")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_domains(guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM link_domain WHERE guild_id = $guild_id";
+        let domains: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .await?
+            .take(0)?;
+
+        Ok(domains)
+    }
+
+    pub async fn remove_domain(guild_id: GuildId, domain: &str) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE link_domain WHERE guild_id = $guild_id AND domain = $domain";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("domain", domain))
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn list(&self) -> DomainList {
+        DomainList::from(self.list.as_str())
+    }
+}
+
+/// # Normaliza un dominio antes de guardarlo o compararlo
+///
+/// - Elimina el protocolo, la ruta y el punto final si se pega un enlace completo
+/// - Convierte cada etiqueta a minúsculas y punycode (las etiquetas con `*` solo se pasan a minúsculas)
+/// - Devuelve `None` si el dominio no es válido
+pub fn normalize_domain(domain: &str) -> Option<String> {
+    let domain = domain.trim();
+    let domain = domain.split_once("://").map_or(domain, |(_, rest)| rest);
+    let domain = domain.split(['/', '?', '#']).next()?;
+    let domain = domain.rsplit_once('@').map_or(domain, |(_, host)| host);
+    let domain = domain.split(':').next()?.trim_end_matches('.');
+    if domain.is_empty() || !domain.contains('.') { return None }
+
+    domain
+        .split('.')
+        .map(|label| {
+            if label.is_empty() { return None }
+            if label.contains('*') { return Some(label.to_lowercase()) }
+            idna::domain_to_ascii(label).ok().filter(|label| !label.is_empty())
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(|labels| labels.join("."))
+}
+
+/// Añade un dominio a la lista de permitidos o bloqueados del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_domain(
+    ctx: Context<'_>,
+    #[description = "Dominio a añadir (ej: ejemplo.com, *.ejemplo.com)"] domain: String,
+    #[description = "Lista a la que se añade el dominio"] list: DomainList,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let Some(domain) = normalize_domain(&domain) else {
+        ctx.say("El dominio no es válido").await?;
+        return Ok(())
+    };
+
+    // Un dominio solo puede estar en una lista a la vez
+    LinkDomain::remove_domain(guild_id, &domain).await?;
+    LinkDomain::new(guild_id, domain.clone(), list).save_to_db().await?;
+    ctx.say(format!("Se ha añadido `{domain}` a la lista de dominios **{}**", list.name())).await?;
+
+    Ok(())
+}
+
+/// Elimina un dominio de la lista de permitidos o bloqueados del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn remove_domain(
+    ctx: Context<'_>,
+    #[description = "Dominio a eliminar"] domain: String,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let Some(domain) = normalize_domain(&domain) else {
+        ctx.say("El dominio no es válido").await?;
+        return Ok(())
+    };
+
+    LinkDomain::remove_domain(guild_id, &domain).await?;
+    ctx.say(format!("Se ha eliminado `{domain}` de las listas de dominios")).await?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use poise::ChoiceParameter;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{DryRun, GuildData};
+
+/// Valor que activa el modo de prueba en todos los módulos
+pub const ALL_MODULES: &str = "all";
+
+/// # Módulos de moderación automática que admiten el modo de prueba
+///
+/// - `Everyone`: Silencio y borrado por `@everyone` o menciones masivas (`handle_everyone`)
+/// - `Warns`: Advertencias y silencio al llegar al máximo de advertencias (`intercept_warn`)
+/// - `Spam`: Silencio y borrado de mensajes repetidos en varios canales (`spam_checker`)
+/// - `Deletions`: Borrado de mensajes con menciones prohibidas o enlaces bloqueados
+/// - `Phishing`: Silencio y borrado por enlaces de phishing
+/// - `Flood`: Acciones por enviar demasiados mensajes o mensajes parecidos
+/// - `Raid`: Silencio o expulsión de los miembros que entran durante un raid
+/// - `AccountAge`: Cuarentena o expulsión de las cuentas demasiado nuevas
+/// - `WordFilter`: Acciones de las reglas del filtro de palabras
+/// - `TextFilter`: Acciones de los filtros de mayúsculas, emojis y texto zalgo
+/// - `Automod`: Acciones de las reglas de moderación automática
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum DryRunModule {
+    #[name = "Everyone"]
+    Everyone,
+    #[name = "Advertencias"]
+    Warns,
+    #[name = "Spam"]
+    Spam,
+    #[name = "Borrado de mensajes"]
+    Deletions,
+    #[name = "Phishing"]
+    Phishing,
+    #[name = "Flood"]
+    Flood,
+    #[name = "Raid"]
+    Raid,
+    #[name = "Antigüedad de cuenta"]
+    AccountAge,
+    #[name = "Filtro de palabras"]
+    WordFilter,
+    #[name = "Filtro de texto"]
+    TextFilter,
+    #[name = "Reglas de moderación automática"]
+    Automod,
+}
+
+impl DryRunModule {
+    pub const ALL: [Self; 11] = [
+        Self::Everyone,
+        Self::Warns,
+        Self::Spam,
+        Self::Deletions,
+        Self::Phishing,
+        Self::Flood,
+        Self::Raid,
+        Self::AccountAge,
+        Self::WordFilter,
+        Self::TextFilter,
+        Self::Automod,
+    ];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Everyone => "everyone",
+            Self::Warns => "warns",
+            Self::Spam => "spam",
+            Self::Deletions => "deletions",
+            Self::Phishing => "phishing",
+            Self::Flood => "flood",
+            Self::Raid => "raid",
+            Self::AccountAge => "account_age",
+            Self::WordFilter => "word_filter",
+            Self::TextFilter => "text_filter",
+            Self::Automod => "automod",
+        }
+    }
+}
+
+/// # Calcula la nueva lista de módulos en modo de prueba
+///
+/// - Sin módulo, se activa o desactiva el modo de prueba en todo el servidor
+/// - Con módulo, se añade o se quita de la lista guardada
+fn update_modules(current: &str, module: Option<DryRunModule>, enabled: bool) -> String {
+    let Some(module) = module else {
+        return if enabled { ALL_MODULES.to_string() } else { String::new() }
+    };
+
+    let mut modules = if current == ALL_MODULES {
+        DryRunModule::ALL.iter().map(|module| module.as_str()).collect::<Vec<_>>()
+    } else {
+        current.split(',').filter(|module| !module.is_empty()).collect::<Vec<_>>()
+    };
+
+    modules.retain(|name| *name != module.as_str());
+    if enabled {
+        modules.push(module.as_str());
+    }
+
+    modules.join(",")
+}
+
+/// Activa o desactiva el modo de prueba: las sanciones automáticas solo se registran en el canal de Logs
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_dry_run(
+    ctx: Context<'_>,
+    #[description = "Activar el modo de prueba"] enabled: bool,
+    #[description = "Módulo a cambiar (si no se indica, se aplica a todos)"] module: Option<DryRunModule>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let status = if enabled { "activado" } else { "desactivado" };
+    let target = module.map_or_else(|| "todos los módulos".to_string(), |module| format!("el módulo **{}**", module.name()));
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    let Some(existing_data) = existing_data else {
+        let modules = update_modules("", module, enabled);
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .dry_run(DryRun::default()
+                .modules(&modules)
+            );
+
+        data.save_to_db().await?;
+        ctx.say(format!("El modo de prueba ha sido {status} para {target}")).await?;
+
+        return Ok(())
+    };
+
+    let current = existing_data.dry_run.modules.unwrap_or_default();
+    let modules = update_modules(&current, module, enabled);
+    let data = DryRun::default().modules(&modules);
+    data.update_field_in_db("dry_run.modules", &modules, &guild_id.to_string()).await?;
+    ctx.say(format!("El modo de prueba ha sido {status} para {target}")).await?;
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, TimeOut};
+
+/// Establece cuántos minutos dura la excepción dinámica del canal de excepciones
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_exception_timer(
+    ctx: Context<'_>,
+    #[description = "Minutos que dura la excepción dinámica"]
+    #[min = 1]
+    #[max = 1440]
+    minutes: u64,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let seconds = (minutes * 60).to_string();
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .time_out(TimeOut::default()
+                .exception(&seconds));
+
+        data.save_to_db().await?;
+        ctx.say(format!("La excepción dinámica se ha establecido a {minutes} minutos")).await?;
+
+        return Ok(())
+    }
+
+    let data = TimeOut::default()
+        .exception(&seconds);
+
+    data.update_field_in_db("time_out.exception", &seconds, &guild_id.to_string()).await?;
+    ctx.say(format!("La excepción dinámica se ha actualizado a {minutes} minutos")).await?;
+
+    Ok(())
+}
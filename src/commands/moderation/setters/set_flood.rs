@@ -0,0 +1,120 @@
+use poise::ChoiceParameter;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{Flood, GuildData};
+
+/// # Acción a tomar al detectar un flood
+///
+/// - `Delete`: Se borran los mensajes del flood
+/// - `Timeout`: Se borran los mensajes y se silencia al autor
+/// - `Slowmode`: Se borran los mensajes y se activa el modo lento en el canal durante unos minutos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum FloodAction {
+    #[name = "Borrar mensajes"]
+    Delete,
+    #[name = "Silenciar"]
+    Timeout,
+    #[name = "Modo lento"]
+    Slowmode,
+}
+
+impl FloodAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Timeout => "timeout",
+            Self::Slowmode => "slowmode",
+        }
+    }
+}
+
+impl From<&str> for FloodAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "timeout" => Self::Timeout,
+            "slowmode" => Self::Slowmode,
+            _ => Self::Delete,
+        }
+    }
+}
+
+/// Configura la detección de flood por cantidad de mensajes y por mensajes parecidos
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_flood(
+    ctx: Context<'_>,
+    #[description = "Activar la detección de flood"] enabled: bool,
+    #[description = "Acción a tomar al detectar un flood"] action: Option<FloodAction>,
+    #[description = "Máximo de mensajes permitidos dentro de la ventana de tiempo"]
+    #[min = 2]
+    #[max = 50]
+    rate: Option<u16>,
+    #[description = "Segundos de la ventana de cantidad de mensajes"]
+    #[min = 1]
+    #[max = 300]
+    rate_window: Option<u64>,
+    #[description = "Porcentaje de parecido para considerar dos mensajes iguales"]
+    #[min = 50]
+    #[max = 100]
+    similarity: Option<u8>,
+    #[description = "Cantidad de mensajes parecidos para considerarlo flood"]
+    #[min = 2]
+    #[max = 20]
+    similar_count: Option<u16>,
+    #[description = "Segundos de la ventana de mensajes parecidos"]
+    #[min = 5]
+    #[max = 600]
+    similar_window: Option<u64>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let status = if enabled { "activada" } else { "desactivada" };
+    let flood = Flood {
+        enabled: Some(enabled.to_string()),
+        action: action.map(|action| action.as_str().to_string()),
+        rate: rate.map(|rate| rate.to_string()),
+        rate_window: rate_window.map(|window| window.to_string()),
+        similarity: similarity.map(|similarity| similarity.to_string()),
+        similar_count: similar_count.map(|count| count.to_string()),
+        similar_window: similar_window.map(|window| window.to_string()),
+    };
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .flood(flood);
+
+        data.save_to_db().await?;
+        ctx.say(format!("La detección de flood ha sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("flood.enabled", &flood.enabled),
+        ("flood.action", &flood.action),
+        ("flood.rate", &flood.rate),
+        ("flood.rate_window", &flood.rate_window),
+        ("flood.similarity", &flood.similarity),
+        ("flood.similar_count", &flood.similar_count),
+        ("flood.similar_window", &flood.similar_window),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        flood.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    let action = action.map_or_else(String::new, |action| format!(" con la acción **{}**", action.name()));
+    ctx.say(format!("La detección de flood ha sido {status}{action}")).await?;
+
+    Ok(())
+}
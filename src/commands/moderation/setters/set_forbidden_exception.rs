@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use serenity::all::{GuildId, Permissions, UserId};
 use surrealdb::Result as SurrealResult;
 use crate::DB;
+use crate::handlers::typing_start::cancel_exception_timer;
 use crate::utils::{CommandResult, Context};
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
@@ -10,6 +11,8 @@ pub struct ForbiddenException {
     pub user_id: UserId,
     pub guild_id: GuildId,
     pub is_active: Option<bool>,
+    /// Momento (timestamp UNIX) en el que expira una excepción dinámica
+    pub expires_at: Option<i64>,
 }
 
 impl ForbiddenException {
@@ -18,6 +21,7 @@ impl ForbiddenException {
             user_id,
             guild_id,
             is_active: Some(is_active),
+            expires_at: None,
         }
     }
 
@@ -68,6 +72,29 @@ impl ForbiddenException {
         Ok(())
     }
 
+    pub async fn set_expiration(user_id: UserId, guild_id: GuildId, expires_at: Option<i64>) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "UPDATE forbidden_exception SET expires_at = $expires_at WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("expires_at", expires_at))
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id)).await?;
+
+        Ok(())
+    }
+
+    /// Obtiene las excepciones dinámicas activas que tienen una expiración pendiente
+    pub async fn get_pending_expirations() -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM forbidden_exception WHERE is_active = true AND expires_at != NONE";
+        let pending: Vec<Self> = DB
+            .query(sql_query)
+            .await?
+            .take(0)?;
+
+        Ok(pending)
+    }
+
     pub async fn have_exception(user_id: UserId) -> SurrealResult<Option<bool>> {
         DB.use_ns("discord-namespace").use_db("discord").await?;
         let sql_query = "SELECT * FROM forbidden_exception WHERE user_id = $user_id";
@@ -112,6 +139,9 @@ pub async fn set_forbidden_exception(
         }
     }
 
+    // Una excepción manual no expira, por lo que se borra la expiración de cualquier excepción dinámica pendiente
+    ForbiddenException::set_expiration(user_id, guild_id, None).await?;
+
     let mut data = ForbiddenException::new(user_id, guild_id, state);
     let existing_data = data.verify_data().await?;
     let user = user_id.to_user(ctx.http()).await?;
@@ -125,5 +155,8 @@ pub async fn set_forbidden_exception(
         poise::say_reply(ctx, format!("El usuario {username} ya ha solicitado una excepción. Actualizando")).await?;
     }
 
+    // Cancelar la excepción dinámica pendiente también sincroniza el AutoMod de Discord
+    cancel_exception_timer(&ctx.serenity_context().http, user_id, guild_id).await;
+
     Ok(())
 }
\ No newline at end of file
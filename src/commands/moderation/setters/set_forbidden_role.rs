@@ -1,6 +1,7 @@
 use serenity::all::Role;
 
 use crate::DB;
+use crate::handlers::misc::native_automod::resync_native_automod;
 use crate::utils::{CommandResult, Context};
 use crate::utils::config::{Forbidden, GuildData};
 
@@ -38,6 +39,7 @@ pub async fn set_forbidden_role(
     // Ejemplo: `forbidden.role_id`
     // Actualizar usando `role_id` creará un nuevo campo en la base de datos fuera del objeto
     data.update_field_in_db("forbidden.role", &role_id, &guild_id.to_string()).await?;
+    resync_native_automod(&ctx.serenity_context().http, guild_id).await;
     ctx.say(format!("Set forbidden role to: **{}**", forbidden_role.name)).await?;
 
     Ok(())
@@ -1,6 +1,7 @@
 use serenity::all::User;
 
 use crate::DB;
+use crate::handlers::misc::native_automod::resync_native_automod;
 use crate::utils::{CommandResult, Context};
 use crate::utils::config::{Forbidden, GuildData};
 
@@ -36,6 +37,7 @@ pub async fn set_forbidden_user(
 
     let data = Forbidden::default().user(&user_id);
     data.update_field_in_db("forbidden.user", &user_id, &guild_id.to_string()).await?;
+    resync_native_automod(&ctx.serenity_context().http, guild_id).await;
     ctx.say(format!("Se ha prohibido mencionar a: **{}**", forbidden_user.name)).await?;
 
     Ok(())
@@ -0,0 +1,54 @@
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GhostPing, GuildData};
+
+/// Activa o desactiva la detección de ghost pings y establece su ventana de detección
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_ghost_ping(
+    ctx: Context<'_>,
+    #[description = "Activar la detección de ghost pings"] enabled: bool,
+    #[description = "Segundos tras el envío en los que borrar una mención se considera ghost ping"]
+    #[min = 5]
+    #[max = 3600]
+    window: Option<u64>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let enabled_value = enabled.to_string();
+    let window_value = window.map(|window| window.to_string());
+    let status = if enabled { "activada" } else { "desactivada" };
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let mut ghost_ping = GhostPing::default().enabled(&enabled_value);
+        if let Some(window_value) = &window_value {
+            ghost_ping = ghost_ping.window(window_value);
+        }
+
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .ghost_ping(ghost_ping);
+
+        data.save_to_db().await?;
+        ctx.say(format!("La detección de ghost pings ha sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let data = GhostPing::default().enabled(&enabled_value);
+    data.update_field_in_db("ghost_ping.enabled", &enabled_value, &guild_id.to_string()).await?;
+    if let Some(window_value) = &window_value {
+        data.update_field_in_db("ghost_ping.window", window_value, &guild_id.to_string()).await?;
+    }
+
+    ctx.say(format!("La detección de ghost pings ha sido {status}")).await?;
+
+    Ok(())
+}
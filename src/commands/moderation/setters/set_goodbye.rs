@@ -0,0 +1,77 @@
+use serenity::all::ChannelId;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{Goodbye, GuildData};
+use crate::utils::template::validate_template;
+
+/// Configura el mensaje y la tarjeta de despedida cuando un miembro abandona el servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_goodbye(
+    ctx: Context<'_>,
+    #[description = "Activar los mensajes de despedida"] enabled: bool,
+    #[description = "The channel to set as the goodbye channel"] channel: Option<ChannelId>,
+    #[description = "Mensaje de despedida. Admite variables como {user} y {time_in_server}"] message: Option<String>,
+    #[description = "Adjuntar la tarjeta de despedida"] card: Option<bool>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    let has_channel = channel.is_some() || existing_data
+        .as_ref()
+        .is_some_and(|data| data.goodbye.channel.is_some());
+
+    if enabled && !has_channel {
+        ctx.say("Debes indicar un canal de despedida para activarla").await?;
+        return Ok(())
+    }
+
+    if let Some(message) = &message {
+        if let Err(why) = validate_template(message) {
+            ctx.say(format!("El mensaje no es válido: {why}")).await?;
+            return Ok(())
+        }
+    }
+
+    let goodbye = Goodbye {
+        enabled: Some(enabled.to_string()),
+        channel: channel.map(|channel| channel.to_string()),
+        message,
+        card: card.map(|card| card.to_string()),
+    };
+
+    let status = if enabled { "activados" } else { "desactivados" };
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .goodbye(goodbye);
+
+        data.save_to_db().await?;
+        ctx.say(format!("Los mensajes de despedida han sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("goodbye.enabled", &goodbye.enabled),
+        ("goodbye.channel", &goodbye.channel),
+        ("goodbye.message", &goodbye.message),
+        ("goodbye.card", &goodbye.card),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        goodbye.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    ctx.say(format!("Los mensajes de despedida han sido {status}")).await?;
+
+    Ok(())
+}
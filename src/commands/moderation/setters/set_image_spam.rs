@@ -0,0 +1,42 @@
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, ImageSpam};
+
+/// Activa o desactiva la detección de la misma imagen enviada en varios canales
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_image_spam(
+    ctx: Context<'_>,
+    #[description = "Activar la detección de spam de imágenes"] enabled: bool,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let enabled_value = enabled.to_string();
+    let status = if enabled { "activada" } else { "desactivada" };
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .image_spam(ImageSpam::default()
+                .enabled(&enabled_value)
+            );
+
+        data.save_to_db().await?;
+        ctx.say(format!("La detección de spam de imágenes ha sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let data = ImageSpam::default().enabled(&enabled_value);
+    data.update_field_in_db("image_spam.enabled", &enabled_value, &guild_id.to_string()).await?;
+    ctx.say(format!("La detección de spam de imágenes ha sido {status}")).await?;
+
+    Ok(())
+}
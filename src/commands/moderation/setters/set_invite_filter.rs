@@ -0,0 +1,138 @@
+use serde::{Deserialize, Serialize};
+use serenity::all::GuildId;
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, InviteFilter};
+
+/// # Servidor asociado cuyas invitaciones se permiten
+///
+/// - `guild_id` es el servidor donde se aplica el filtro
+/// - `partner_id` es el servidor al que apuntan las invitaciones permitidas
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct InvitePartner {
+    pub guild_id: GuildId,
+    pub partner_id: GuildId,
+}
+
+impl InvitePartner {
+    pub const fn new(guild_id: GuildId, partner_id: GuildId) -> Self {
+        Self { guild_id, partner_id }
+    }
+
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("invite_partner")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_partners(guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM invite_partner WHERE guild_id = $guild_id";
+        let partners: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .await?
+            .take(0)?;
+
+        Ok(partners)
+    }
+
+    pub async fn remove_partner(guild_id: GuildId, partner_id: GuildId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE invite_partner WHERE guild_id = $guild_id AND partner_id = $partner_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("partner_id", partner_id))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Activa o desactiva el filtro de invitaciones a otros servidores
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_invite_filter(
+    ctx: Context<'_>,
+    #[description = "Activar el filtro de invitaciones"] enabled: bool,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let enabled_value = enabled.to_string();
+    let status = if enabled { "activado" } else { "desactivado" };
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .invite_filter(InviteFilter::default()
+                .enabled(&enabled_value)
+            );
+
+        data.save_to_db().await?;
+        ctx.say(format!("El filtro de invitaciones ha sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let data = InviteFilter::default().enabled(&enabled_value);
+    data.update_field_in_db("invite_filter.enabled", &enabled_value, &guild_id.to_string()).await?;
+    ctx.say(format!("El filtro de invitaciones ha sido {status}")).await?;
+
+    Ok(())
+}
+
+/// Permite las invitaciones a un servidor asociado
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn add_invite_partner(
+    ctx: Context<'_>,
+    #[description = "The id of the partner server"] partner: GuildId,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+
+    // Se elimina antes de guardarlo para no duplicar el servidor asociado
+    InvitePartner::remove_partner(guild_id, partner).await?;
+    InvitePartner::new(guild_id, partner).save_to_db().await?;
+    ctx.say(format!("Se permiten las invitaciones al servidor `{partner}`")).await?;
+
+    Ok(())
+}
+
+/// Deja de permitir las invitaciones a un servidor asociado
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn remove_invite_partner(
+    ctx: Context<'_>,
+    #[description = "The id of the partner server"] partner: GuildId,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    InvitePartner::remove_partner(guild_id, partner).await?;
+    ctx.say(format!("Ya no se permiten las invitaciones al servidor `{partner}`")).await?;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, MassMention};
+
+/// Activa la detección de menciones masivas y establece sus límites por mensaje y por ventana de tiempo
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_mass_mention(
+    ctx: Context<'_>,
+    #[description = "Activar la detección de menciones masivas"] enabled: bool,
+    #[description = "Máximo de usuarios y roles distintos mencionados en un mensaje (por defecto: 10)"]
+    #[min = 1]
+    per_message: Option<u16>,
+    #[description = "Máximo de menciones sumando los mensajes recientes del usuario (por defecto: 20)"]
+    #[min = 1]
+    per_window: Option<u16>,
+    #[description = "Segundos que abarca la ventana de menciones recientes (por defecto: 60)"]
+    #[min = 5]
+    #[max = 3600]
+    window: Option<u64>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let mass_mention = MassMention {
+        enabled: Some(enabled.to_string()),
+        per_message: per_message.map(|value| value.to_string()),
+        per_window: per_window.map(|value| value.to_string()),
+        window: window.map(|value| value.to_string()),
+    };
+
+    let status = if enabled { "activada" } else { "desactivada" };
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .mass_mention(mass_mention);
+
+        data.save_to_db().await?;
+        ctx.say(format!("La detección de menciones masivas ha sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("mass_mention.enabled", &mass_mention.enabled),
+        ("mass_mention.per_message", &mass_mention.per_message),
+        ("mass_mention.per_window", &mass_mention.per_window),
+        ("mass_mention.window", &mass_mention.window),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        mass_mention.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    ctx.say(format!("La detección de menciones masivas ha sido {status}")).await?;
+
+    Ok(())
+}
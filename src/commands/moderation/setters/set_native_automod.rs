@@ -0,0 +1,49 @@
+use crate::DB;
+use crate::handlers::misc::native_automod::sync_native_automod;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, NativeAutomod};
+
+/// Refleja las menciones prohibidas y el filtro de palabras en el AutoMod de Discord
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_native_automod(
+    ctx: Context<'_>,
+    #[description = "Sincronizar las reglas con el AutoMod de Discord"] enabled: bool,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let native_automod = NativeAutomod::default().enabled(enabled.to_string());
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .native_automod(native_automod);
+
+        data.save_to_db().await?;
+    } else {
+        native_automod.update_field_in_db("native_automod.enabled", &enabled.to_string(), &guild_id.to_string()).await?;
+    }
+
+    // El Bot necesita el permiso `Gestionar servidor` para crear las reglas
+    if let Err(why) = sync_native_automod(&ctx.serenity_context().http, guild_id).await {
+        ctx.say(format!("No se han podido sincronizar las reglas de AutoMod:\n```\n{why}\n```")).await?;
+        return Ok(())
+    }
+
+    let message = if enabled {
+        "Las menciones prohibidas y el filtro de palabras se bloquearán con el AutoMod de Discord"
+    } else {
+        "Se han eliminado las reglas de AutoMod creadas por el Bot"
+    };
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
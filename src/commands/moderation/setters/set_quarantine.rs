@@ -0,0 +1,92 @@
+use serenity::all::{Role, User};
+
+use crate::DB;
+use crate::handlers::misc::punishment::release_member;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, Quarantine};
+
+/// Usa un rol de cuarentena en lugar del aislamiento de Discord para silenciar a los miembros
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_quarantine(
+    ctx: Context<'_>,
+    #[description = "Usar el rol de cuarentena en lugar del aislamiento"] enabled: bool,
+    #[description = "The role to set as the quarantine role"] role: Option<Role>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    let has_role = role.is_some() || existing_data
+        .as_ref()
+        .is_some_and(|data| data.quarantine.role.is_some());
+
+    if enabled && !has_role {
+        ctx.say("Debes indicar un rol de cuarentena para activarla").await?;
+        return Ok(())
+    }
+
+    let quarantine = Quarantine {
+        enabled: Some(enabled.to_string()),
+        role: role.as_ref().map(|role| role.id.to_string()),
+    };
+
+    let message = if enabled {
+        "Los miembros silenciados recibirán el rol de cuarentena en lugar del aislamiento"
+    } else {
+        "Los miembros silenciados volverán a recibir el aislamiento de Discord"
+    };
+
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .quarantine(quarantine);
+
+        data.save_to_db().await?;
+        ctx.say(message).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("quarantine.enabled", &quarantine.enabled),
+        ("quarantine.role", &quarantine.role),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        quarantine.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Termina la cuarentena de un miembro antes de tiempo y le devuelve sus roles
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn release_quarantine(
+    ctx: Context<'_>,
+    #[description = "The user to release from quarantine"] user: User,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    if release_member(ctx.http(), guild_id, user.id).await? {
+        ctx.say(format!("<@{}> ha salido de la cuarentena y ha recuperado sus roles", user.id)).await?;
+    } else {
+        ctx.say(format!("<@{}> no está en cuarentena", user.id)).await?;
+    }
+
+    Ok(())
+}
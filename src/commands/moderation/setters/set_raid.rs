@@ -0,0 +1,102 @@
+use poise::ChoiceParameter;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, Raid};
+
+/// # Acción a tomar con los miembros que entran durante un raid
+///
+/// - `None`: Solo se omite la bienvenida, se activa el modo lento y se avisa a los moderadores
+/// - `Timeout`: Además se silencia a cada miembro nuevo
+/// - `Kick`: Además se expulsa a cada miembro nuevo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum RaidAction {
+    #[name = "Solo avisar"]
+    None,
+    #[name = "Silenciar"]
+    Timeout,
+    #[name = "Expulsar"]
+    Kick,
+}
+
+impl RaidAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Timeout => "timeout",
+            Self::Kick => "kick",
+        }
+    }
+}
+
+impl From<&str> for RaidAction {
+    fn from(value: &str) -> Self {
+        match value {
+            "timeout" => Self::Timeout,
+            "kick" => Self::Kick,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Configura la detección de raids por cantidad de miembros nuevos en poco tiempo
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_raid(
+    ctx: Context<'_>,
+    #[description = "Activar la detección de raids"] enabled: bool,
+    #[description = "Cantidad de miembros nuevos para considerarlo un raid"]
+    #[min = 3]
+    #[max = 100]
+    joins: Option<u16>,
+    #[description = "Segundos de la ventana de tiempo"]
+    #[min = 1]
+    #[max = 600]
+    window: Option<u64>,
+    #[description = "Acción a tomar con los miembros que entran durante el raid"] action: Option<RaidAction>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let status = if enabled { "activada" } else { "desactivada" };
+    let raid = Raid {
+        enabled: Some(enabled.to_string()),
+        joins: joins.map(|joins| joins.to_string()),
+        window: window.map(|window| window.to_string()),
+        action: action.map(|action| action.as_str().to_string()),
+    };
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .raid(raid);
+
+        data.save_to_db().await?;
+        ctx.say(format!("La detección de raids ha sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("raid.enabled", &raid.enabled),
+        ("raid.joins", &raid.joins),
+        ("raid.window", &raid.window),
+        ("raid.action", &raid.action),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        raid.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    let action = action.map_or_else(String::new, |action| format!(" con la acción **{}**", action.name()));
+    ctx.say(format!("La detección de raids ha sido {status}{action}")).await?;
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use poise::ChoiceParameter;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{Forbidden, GuildData};
+
+/// # Política para las respuestas con mención al usuario prohibido
+///
+/// - `Allow`: Se permite responder con `@` activado
+/// - `Warn`: Se envía una advertencia sin contar `warns` ni borrar el mensaje
+/// - `Escalate`: Se aplica el sistema de advertencias completo (comportamiento por defecto)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum ReplyPingPolicy {
+    #[name = "Permitir"]
+    Allow,
+    #[name = "Solo advertir"]
+    Warn,
+    #[name = "Sanción completa"]
+    Escalate,
+}
+
+impl ReplyPingPolicy {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Warn => "warn",
+            Self::Escalate => "escalate",
+        }
+    }
+}
+
+impl From<&str> for ReplyPingPolicy {
+    fn from(value: &str) -> Self {
+        match value {
+            "allow" => Self::Allow,
+            "warn" => Self::Warn,
+            _ => Self::Escalate,
+        }
+    }
+}
+
+/// Establece qué hacer cuando se responde a un mensaje del usuario prohibido con `@` activado
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_reply_ping_policy(
+    ctx: Context<'_>,
+    #[description = "Acción a tomar al responder con @ al usuario prohibido"] policy: ReplyPingPolicy,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let policy_name = policy.as_str();
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .forbidden(Forbidden::default()
+                .reply_ping(policy_name)
+            );
+
+        data.save_to_db().await?;
+        ctx.say(format!("Política de respuestas con @ establecida a: **{}**", policy.name())).await?;
+        return Ok(())
+    }
+
+    let data = Forbidden::default().reply_ping(policy_name);
+    data.update_field_in_db("forbidden.reply_ping", policy_name, &guild_id.to_string()).await?;
+    ctx.say(format!("Política de respuestas con @ actualizada a: **{}**", policy.name())).await?;
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, Spam};
+
+/// Establece cuántos canales y en cuántos segundos un mensaje repetido se considera spam
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_spam_thresholds(
+    ctx: Context<'_>,
+    #[description = "Cantidad de canales distintos con el mismo mensaje para considerarlo spam"]
+    #[min = 2]
+    #[max = 10]
+    channels: u8,
+    #[description = "Segundos máximos entre mensajes repetidos para seguir rastreándolos"]
+    #[min = 1]
+    #[max = 120]
+    window: Option<u64>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let channels = channels.to_string();
+    let window = window.map(|window| window.to_string());
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let mut spam = Spam::default().channels(&channels);
+        if let Some(window) = &window {
+            spam = spam.window(window);
+        }
+
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .spam(spam);
+
+        data.save_to_db().await?;
+        ctx.say(format!("Se ha establecido el límite de spam a {channels} canales")).await?;
+
+        return Ok(())
+    }
+
+    let data = Spam::default().channels(&channels);
+    data.update_field_in_db("spam.channels", &channels, &guild_id.to_string()).await?;
+    if let Some(window) = &window {
+        data.update_field_in_db("spam.window", window, &guild_id.to_string()).await?;
+    }
+
+    ctx.say(format!("Se ha actualizado el límite de spam a {channels} canales")).await?;
+
+    Ok(())
+}
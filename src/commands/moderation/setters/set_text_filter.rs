@@ -0,0 +1,143 @@
+use poise::ChoiceParameter;
+use serenity::all::ChannelId;
+
+use crate::DB;
+use crate::commands::moderation::filter::FilterAction;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, TextFilter};
+
+/// Configura los filtros de mayúsculas, emojis y texto zalgo
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_text_filter(
+    ctx: Context<'_>,
+    #[description = "Activar los filtros de texto"] enabled: bool,
+    #[description = "Acción a tomar al detectar un mensaje (por defecto: borrar)"] action: Option<FilterAction>,
+    #[description = "Porcentaje máximo de mayúsculas"]
+    #[min = 50]
+    #[max = 100]
+    caps_ratio: Option<u8>,
+    #[description = "Letras mínimas para revisar las mayúsculas"]
+    #[min = 1]
+    #[max = 200]
+    caps_min_length: Option<u16>,
+    #[description = "Máximo de emojis por mensaje"]
+    #[min = 1]
+    #[max = 100]
+    emoji_max: Option<u16>,
+    #[description = "Porcentaje máximo de caracteres combinados (texto zalgo)"]
+    #[min = 10]
+    #[max = 100]
+    zalgo_ratio: Option<u8>,
+    #[description = "Caracteres mínimos para revisar el texto zalgo"]
+    #[min = 1]
+    #[max = 200]
+    zalgo_min_length: Option<u16>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let status = if enabled { "activados" } else { "desactivados" };
+    let text_filter = TextFilter {
+        enabled: Some(enabled.to_string()),
+        action: action.map(|action| action.as_str().to_string()),
+        caps_ratio: caps_ratio.map(|ratio| ratio.to_string()),
+        caps_min_length: caps_min_length.map(|length| length.to_string()),
+        emoji_max: emoji_max.map(|max| max.to_string()),
+        zalgo_ratio: zalgo_ratio.map(|ratio| ratio.to_string()),
+        zalgo_min_length: zalgo_min_length.map(|length| length.to_string()),
+        exempt_channels: None,
+    };
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .text_filter(text_filter);
+
+        data.save_to_db().await?;
+        ctx.say(format!("Los filtros de texto han sido {status}")).await?;
+
+        return Ok(())
+    }
+
+    let fields = [
+        ("text_filter.enabled", &text_filter.enabled),
+        ("text_filter.action", &text_filter.action),
+        ("text_filter.caps_ratio", &text_filter.caps_ratio),
+        ("text_filter.caps_min_length", &text_filter.caps_min_length),
+        ("text_filter.emoji_max", &text_filter.emoji_max),
+        ("text_filter.zalgo_ratio", &text_filter.zalgo_ratio),
+        ("text_filter.zalgo_min_length", &text_filter.zalgo_min_length),
+    ];
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        text_filter.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    let action = action.map_or_else(String::new, |action| format!(" con la acción **{}**", action.name()));
+    ctx.say(format!("Los filtros de texto han sido {status}{action}")).await?;
+
+    Ok(())
+}
+
+/// Exime o deja de eximir un canal de los filtros de mayúsculas, emojis y texto zalgo
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_text_filter_exemption(
+    ctx: Context<'_>,
+    #[description = "The channel to exempt"] channel: ChannelId,
+    #[description = "Eximir el canal de los filtros de texto"] exempt: bool,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let status = if exempt { "está exento de" } else { "ya no está exento de" };
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    let current = existing_data
+        .as_ref()
+        .and_then(|data| data.text_filter.exempt_channels.clone())
+        .unwrap_or_default();
+
+    let mut channels = current
+        .split(',')
+        .filter(|channel_id| !channel_id.is_empty() && *channel_id != channel.to_string())
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    if exempt {
+        channels.push(channel.to_string());
+    }
+
+    let exempt_channels = channels.join(",");
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .text_filter(TextFilter::default()
+                .exempt_channels(&exempt_channels)
+            );
+
+        data.save_to_db().await?;
+        ctx.say(format!("El canal <#{channel}> {status} los filtros de texto")).await?;
+
+        return Ok(())
+    }
+
+    let data = TextFilter::default().exempt_channels(&exempt_channels);
+    data.update_field_in_db("text_filter.exempt_channels", &exempt_channels, &guild_id.to_string()).await?;
+    ctx.say(format!("El canal <#{channel}> {status} los filtros de texto")).await?;
+
+    Ok(())
+}
@@ -1,5 +1,6 @@
 use crate::utils::{CommandResult, Context};
 use crate::utils::config::{GuildData, Messages};
+use crate::utils::template::validate_template;
 
 #[poise::command(
     prefix_command,
@@ -14,7 +15,11 @@ pub async fn set_time_out_message(
     #[description = "The message to set as the time out message"] time_out_message: String,
 ) -> CommandResult {
     let guild_id = ctx.guild_id().unwrap();
-    
+    if let Err(why) = validate_template(&time_out_message) {
+        ctx.say(format!("El mensaje no es válido: {why}")).await?;
+        return Ok(())
+    }
+
     let existing_data = GuildData::verify_data(guild_id).await?;
     if existing_data.is_none() {
         let data = GuildData::default()
@@ -23,7 +23,7 @@ pub async fn set_timeout_timer(
         "5 Minutos" => "300",
         "30 Minutos" => "1800",
         "60 Minutos" => "3600",
-        "1 Semana" => "604_800",
+        "1 Semana" => "604800",
         _ => "60",
     };
     
@@ -55,7 +55,7 @@ pub async fn set_timeout_timer(
         "300" => "5 Minutos",
         "1800" => "30 Minutos",
         "3600" => "60 Minutos",
-        "604_800" => "1 Semana",
+        "604800" => "1 Semana",
         _ => "1 Minuto",
     };
 
@@ -0,0 +1,123 @@
+use poise::serenity_prelude as serenity;
+use tokio::fs;
+
+use crate::DB;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, WarnEmbed};
+use crate::utils::embeds::parse_hex_color;
+use crate::utils::template::validate_template;
+
+/// Tamaño máximo de la imagen de consejo (8 MB)
+const MAX_IMAGE_SIZE: u32 = 8 * 1024 * 1024;
+
+/// Personaliza la imagen, el color y el pie del embed de advertencia
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn set_warn_embed(
+    ctx: Context<'_>,
+    #[description = "Imagen de consejo que se adjunta a la advertencia"] image: Option<serenity::Attachment>,
+    #[description = "Color del embed en hexadecimal (ej: #FFAA00)"] color: Option<String>,
+    #[description = "Pie del embed. Admite variables como {max_warns} y {duration}"] footer: Option<String>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+
+    if image.is_none() && color.is_none() && footer.is_none() {
+        ctx.say("Debes establecer al menos la imagen, el color o el pie del embed").await?;
+        return Ok(())
+    }
+
+    if let Some(color) = &color {
+        if parse_hex_color(color).is_none() {
+            ctx.say("El color no es válido, usa el formato hexadecimal `#FFAA00`").await?;
+            return Ok(())
+        }
+    }
+
+    if let Some(footer) = &footer {
+        if let Err(why) = validate_template(footer) {
+            ctx.say(format!("El pie no es válido: {why}")).await?;
+            return Ok(())
+        }
+    }
+
+    let image_path = match &image {
+        Some(image) => {
+            let is_image = image.content_type
+                .as_deref()
+                .is_some_and(|content_type| content_type.starts_with("image/"));
+
+            if !is_image {
+                ctx.say("El archivo adjunto debe ser una imagen").await?;
+                return Ok(())
+            }
+
+            if image.size > MAX_IMAGE_SIZE {
+                ctx.say("La imagen no puede superar los 8 MB").await?;
+                return Ok(())
+            }
+
+            // Cada servidor guarda su imagen en su propia carpeta para no pisar la de otros
+            let extension = image.filename
+                .rsplit_once('.')
+                .map(|(_, extension)| extension)
+                .filter(|extension| extension.chars().all(|character| character.is_ascii_alphanumeric()))
+                .unwrap_or("png");
+            let directory = format!("./assets/guilds/{guild_id}");
+            let path = format!("{directory}/sugerencia.{extension}");
+            let bytes = image.download().await?;
+            fs::create_dir_all(&directory).await?;
+            fs::write(&path, bytes).await?;
+
+            Some(path)
+        }
+        None => None,
+    };
+
+    let mut warn_embed = WarnEmbed::default();
+    if let Some(image_path) = &image_path {
+        warn_embed = warn_embed.image(image_path);
+    }
+
+    if let Some(color) = &color {
+        warn_embed = warn_embed.color(color);
+    }
+
+    if let Some(footer) = &footer {
+        warn_embed = warn_embed.footer(footer);
+    }
+
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .warn_embed(warn_embed);
+
+        data.save_to_db().await?;
+        ctx.say("Se ha personalizado el embed de advertencia").await?;
+
+        return Ok(())
+    }
+
+    if let Some(image_path) = &image_path {
+        warn_embed.update_field_in_db("warn_embed.image", image_path, &guild_id.to_string()).await?;
+    }
+
+    if let Some(color) = &color {
+        warn_embed.update_field_in_db("warn_embed.color", color, &guild_id.to_string()).await?;
+    }
+
+    if let Some(footer) = &footer {
+        warn_embed.update_field_in_db("warn_embed.footer", footer, &guild_id.to_string()).await?;
+    }
+
+    ctx.say("Se ha actualizado el embed de advertencia").await?;
+
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use crate::DB;
 use crate::utils::{CommandResult, Context};
 use crate::utils::config::{GuildData, Messages};
+use crate::utils::template::validate_template;
 
 #[poise::command(
     prefix_command,
@@ -16,6 +17,11 @@ pub async fn set_warn_message(
 ) -> CommandResult {
     DB.use_ns("discord-namespace").use_db("discord").await?;
     let guild_id = ctx.guild_id().unwrap();
+    if let Err(why) = validate_template(&warn_message) {
+        ctx.say(format!("El mensaje no es válido: {why}")).await?;
+        return Ok(())
+    }
+
     let existing_data = GuildData::verify_data(guild_id).await?;
 
     if existing_data.is_none() {
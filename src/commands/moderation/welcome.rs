@@ -0,0 +1,374 @@
+use std::fmt::Write;
+use std::fs::remove_file;
+
+use poise::{ChoiceParameter, CreateReply};
+use poise::serenity_prelude as serenity;
+use serde::{Deserialize, Serialize};
+use serenity::all::{CreateAttachment, GuildId};
+use surrealdb::Result as SurrealResult;
+use tokio::fs;
+
+use crate::DB;
+use crate::handlers::goodbye::{render_goodbye_card, time_in_server};
+use crate::handlers::welcome::render_welcome_card;
+use crate::utils::autocomplete::welcome_texts;
+use crate::utils::{CommandResult, Context};
+use crate::utils::config::{GuildData, WelcomeCard};
+use crate::utils::embeds::parse_hex_color;
+use crate::utils::template::{validate_template, TemplateContext};
+
+/// Tamaño máximo del fondo de la tarjeta de bienvenida (8 MB)
+const MAX_BACKGROUND_SIZE: u32 = 8 * 1024 * 1024;
+
+/// # Forma del avatar en la tarjeta de bienvenida
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum AvatarShape {
+    #[name = "Círculo"]
+    Circle,
+    #[name = "Cuadrado"]
+    Square,
+}
+
+impl AvatarShape {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Circle => "circle",
+            Self::Square => "square",
+        }
+    }
+}
+
+impl From<&str> for AvatarShape {
+    fn from(value: &str) -> Self {
+        match value {
+            "square" => Self::Square,
+            _ => Self::Circle,
+        }
+    }
+}
+
+/// # Fuentes incluidas en `assets/` para el texto de la tarjeta
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum WelcomeFont {
+    #[name = "PT Serif"]
+    Regular,
+    #[name = "PT Serif Cursiva"]
+    Italic,
+}
+
+impl WelcomeFont {
+    pub const fn path(self) -> &'static str {
+        match self {
+            Self::Regular => "assets/PTSerif-Regular.ttf",
+            Self::Italic => "assets/PTSerif-Italic.ttf",
+        }
+    }
+}
+
+/// # Línea de texto de la tarjeta de bienvenida
+///
+/// - `text` es una plantilla, por lo que admite variables como `{user}` o `{member_count}`
+/// - `color` es un color hexadecimal (`#FFAA00`)
+/// - `x` e `y` indican la esquina superior izquierda del texto, y `size` la altura en píxeles
+/// - `goodbye` indica que la línea pertenece a la tarjeta de despedida
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WelcomeText {
+    pub guild_id: GuildId,
+    pub text: String,
+    pub font: WelcomeFont,
+    pub color: String,
+    pub x: i32,
+    pub y: i32,
+    pub size: u32,
+    #[serde(default)]
+    pub goodbye: bool,
+}
+
+impl WelcomeText {
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("welcome_text")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_texts(guild_id: GuildId) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM welcome_text WHERE guild_id = $guild_id";
+        let texts: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .await?
+            .take(0)?;
+
+        Ok(texts)
+    }
+
+    pub async fn remove_text(guild_id: GuildId, text: &str, goodbye: bool) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE welcome_text WHERE guild_id = $guild_id AND text = $text AND goodbye = $goodbye";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("text", text))
+            .bind(("goodbye", goodbye))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Personaliza la tarjeta de bienvenida del servidor
+#[poise::command(
+    prefix_command,
+    slash_command,
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral,
+    subcommands("welcome_background", "welcome_avatar", "welcome_add_text", "welcome_remove_text", "welcome_preview"),
+    subcommand_required
+)]
+pub async fn welcome(_: Context<'_>) -> CommandResult {
+    Ok(())
+}
+
+/// # Guarda los campos indicados de la tarjeta de bienvenida
+async fn save_card_fields(guild_id: GuildId, card: WelcomeCard, fields: &[(&str, &Option<String>)]) -> CommandResult {
+    let existing_data = GuildData::verify_data(guild_id).await?;
+    if existing_data.is_none() {
+        let data = GuildData::default()
+            .guild_id(guild_id)
+            .welcome_card(card);
+
+        data.save_to_db().await?;
+        return Ok(())
+    }
+
+    for (field, value) in fields {
+        let Some(value) = value else { continue };
+        card.update_field_in_db(field, value, &guild_id.to_string()).await?;
+    }
+
+    Ok(())
+}
+
+/// Sube el fondo de la tarjeta de bienvenida
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "background",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn welcome_background(
+    ctx: Context<'_>,
+    #[description = "Imagen de fondo de la tarjeta"] background: serenity::Attachment,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+
+    if background.size > MAX_BACKGROUND_SIZE {
+        ctx.say("La imagen no puede superar los 8 MB").await?;
+        return Ok(())
+    }
+
+    // Se comprueba que la imagen se pueda abrir antes de guardarla
+    let bytes = background.download().await?;
+    let Ok(decoded) = image::load_from_memory(&bytes) else {
+        ctx.say("El archivo adjunto debe ser una imagen").await?;
+        return Ok(())
+    };
+
+    // Cada servidor guarda su imagen en su propia carpeta para no pisar la de otros
+    let directory = format!("./assets/guilds/{guild_id}");
+    let path = format!("{directory}/welcome.png");
+    fs::create_dir_all(&directory).await?;
+    decoded.save(&path)?;
+
+    let card = WelcomeCard::default().background(&path);
+    save_card_fields(guild_id, card.clone(), &[("welcome_card.background", &card.background)]).await?;
+    ctx.say("Se ha actualizado el fondo de la tarjeta de bienvenida").await?;
+
+    Ok(())
+}
+
+/// Cambia la posición, el tamaño y la forma del avatar en la tarjeta de bienvenida
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "avatar",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn welcome_avatar(
+    ctx: Context<'_>,
+    #[description = "Posición horizontal del avatar en píxeles"]
+    #[max = 4096]
+    x: Option<u32>,
+    #[description = "Posición vertical del avatar en píxeles"]
+    #[max = 4096]
+    y: Option<u32>,
+    #[description = "Tamaño del avatar en píxeles"]
+    #[min = 16]
+    #[max = 1024]
+    size: Option<u32>,
+    #[description = "Forma del avatar"] shape: Option<AvatarShape>,
+) -> CommandResult {
+    DB.use_ns("discord-namespace").use_db("discord").await?;
+    let guild_id = ctx.guild_id().unwrap();
+    let card = WelcomeCard {
+        background: None,
+        avatar_x: x.map(|x| x.to_string()),
+        avatar_y: y.map(|y| y.to_string()),
+        avatar_size: size.map(|size| size.to_string()),
+        avatar_shape: shape.map(|shape| shape.as_str().to_string()),
+    };
+
+    let fields = [
+        ("welcome_card.avatar_x", &card.avatar_x),
+        ("welcome_card.avatar_y", &card.avatar_y),
+        ("welcome_card.avatar_size", &card.avatar_size),
+        ("welcome_card.avatar_shape", &card.avatar_shape),
+    ];
+
+    save_card_fields(guild_id, card.clone(), &fields).await?;
+    ctx.say("Se ha actualizado el avatar de la tarjeta de bienvenida").await?;
+
+    Ok(())
+}
+
+/// Añade una línea de texto a la tarjeta de bienvenida
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "add_text",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn welcome_add_text(
+    ctx: Context<'_>,
+    #[description = "Texto de la línea. Admite variables como {user} y {member_count}"] text: String,
+    #[description = "Posición horizontal del texto en píxeles"]
+    #[max = 4096]
+    x: u32,
+    #[description = "Posición vertical del texto en píxeles"]
+    #[max = 4096]
+    y: u32,
+    #[description = "Altura del texto en píxeles (por defecto: 48)"]
+    #[min = 8]
+    #[max = 512]
+    size: Option<u32>,
+    #[description = "Fuente del texto (por defecto: PT Serif)"] font: Option<WelcomeFont>,
+    #[description = "Color del texto en hexadecimal (por defecto: #FFFFFF)"] color: Option<String>,
+    #[description = "Añadir la línea a la tarjeta de despedida en lugar de a la de bienvenida"] goodbye: Option<bool>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    if let Err(why) = validate_template(&text) {
+        ctx.say(format!("El texto no es válido: {why}")).await?;
+        return Ok(())
+    }
+
+    let color = color.unwrap_or_else(|| "#FFFFFF".to_string());
+    if parse_hex_color(&color).is_none() {
+        ctx.say("El color no es válido, usa el formato hexadecimal `#FFAA00`").await?;
+        return Ok(())
+    }
+
+    let font = font.unwrap_or(WelcomeFont::Regular);
+    let line = WelcomeText {
+        guild_id,
+        text: text.clone(),
+        font,
+        color,
+        x: i32::try_from(x)?,
+        y: i32::try_from(y)?,
+        size: size.unwrap_or(48),
+        goodbye: goodbye.unwrap_or(false),
+    };
+
+    // Se elimina antes de guardarla para no duplicar la línea
+    WelcomeText::remove_text(guild_id, &text, line.goodbye).await?;
+    line.save_to_db().await?;
+    let card = if line.goodbye { "despedida" } else { "bienvenida" };
+    ctx.say(format!("Se ha añadido la línea `{text}` ({}) a la tarjeta de {card}", font.name())).await?;
+
+    Ok(())
+}
+
+/// Elimina una línea de texto de la tarjeta de bienvenida o de despedida
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "remove_text",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn welcome_remove_text(
+    ctx: Context<'_>,
+    #[description = "Línea a eliminar"]
+    #[autocomplete = "welcome_texts"]
+    text: String,
+    #[description = "Eliminar la línea de la tarjeta de despedida en lugar de la de bienvenida"] goodbye: Option<bool>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let goodbye = goodbye.unwrap_or(false);
+    WelcomeText::remove_text(guild_id, &text, goodbye).await?;
+    let card = if goodbye { "despedida" } else { "bienvenida" };
+    ctx.say(format!("Se ha eliminado la línea `{text}` de la tarjeta de {card}")).await?;
+
+    Ok(())
+}
+
+/// Muestra la tarjeta de bienvenida o de despedida usando al autor del comando como ejemplo
+#[poise::command(
+    prefix_command,
+    slash_command,
+    rename = "preview",
+    category = "Moderator",
+    required_permissions = "MODERATE_MEMBERS",
+    guild_only,
+    ephemeral
+)]
+pub async fn welcome_preview(
+    ctx: Context<'_>,
+    #[description = "Mostrar la tarjeta de despedida"] goodbye: Option<bool>,
+) -> CommandResult {
+    let guild_id = ctx.guild_id().unwrap();
+    let goodbye = goodbye.unwrap_or(false);
+    let member = guild_id.member(ctx.http(), ctx.author().id).await?;
+    let file = if goodbye {
+        let template_context = TemplateContext::for_member(ctx.serenity_context(), &member)
+            .time_in_server(time_in_server(member.joined_at));
+        render_goodbye_card(guild_id, &member.user, &template_context).await?
+    } else {
+        render_welcome_card(ctx.serenity_context(), &member).await?
+    };
+
+    let attachment = CreateAttachment::path(&file).await?;
+    let texts = WelcomeText::get_texts(guild_id).await?;
+    let card = if goodbye { "despedida" } else { "bienvenida" };
+    let mut description = format!("**Vista previa de la tarjeta de {card}**\n");
+    for line in texts.iter().filter(|line| line.goodbye == goodbye) {
+        writeln!(description, "- `{}` ({}, {}px, {}) en ({}, {})", line.text, line.font.name(), line.size, line.color, line.x, line.y)?;
+    }
+
+    ctx.send(CreateReply::default()
+        .content(description)
+        .attachment(attachment)
+    ).await?;
+
+    remove_file(file)?;
+
+    Ok(())
+}
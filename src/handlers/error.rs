@@ -1,8 +1,21 @@
 use poise::FrameworkError;
+use serenity::all::HttpError;
 
 use crate::utils::Data;
 use crate::utils::Error;
 
+/// Códigos de error de Discord para los recursos que ya no existen
+pub const UNKNOWN_CHANNEL: isize = 10003;
+pub const UNKNOWN_INVITE: isize = 10006;
+pub const UNKNOWN_MEMBER: isize = 10007;
+
+/// # Comprueba si Discord rechazó la petición con un código de error concreto
+///
+/// - Permite distinguir un recurso borrado de un límite de peticiones o un fallo temporal de Discord
+pub fn is_discord_error(why: &serenity::Error, code: isize) -> bool {
+    matches!(why, serenity::Error::Http(HttpError::UnsuccessfulRequest(response)) if response.error.code == code)
+}
+
 pub async fn handler(error: FrameworkError<'_, Data, Error>) {
     match error {
         FrameworkError::Setup { error, .. } => {
@@ -2,9 +2,11 @@ use poise::{FrameworkContext, serenity_prelude as serenity};
 use serenity::FullEvent;
 
 use crate::{DB, debug};
-use crate::handlers::{interactions, typing_start, welcome};
+use crate::handlers::{goodbye, interactions, member_join, typing_start};
 use crate::handlers::messages::{deleted, edited, sent};
-use crate::handlers::misc::reaction_add;
+use crate::handlers::misc::{native_automod, reaction_add};
+use crate::handlers::misc::auto_role::PendingAutoRole;
+use crate::handlers::misc::captcha::cancel_captcha;
 use crate::utils::{CommandResult, Data, Error};
 
 /// # Esta función maneja los eventos de Discord
@@ -15,7 +17,10 @@ use crate::utils::{CommandResult, Data, Error};
 /// - `MessageDelete`: Maneja los mensajes eliminados en un servidor
 /// - `MessageUpdate`: Maneja los mensajes editados en un servidor
 /// - `GuildMemberAddition`: Maneja la llegada de un nuevo miembro a un servidor
+/// - `GuildMemberRemoval`: Maneja la salida de un miembro de un servidor
 /// - `ReactionAdd`: Maneja las reacciones a los mensajes
+/// - `InteractionCreate`: Maneja los botones y formularios
+/// - `AutoModActionExecution`: Suma advertencias por los mensajes bloqueados por AutoMod
 pub async fn event_handler(
     ctx: &serenity::Context,
     event: &FullEvent,
@@ -27,10 +32,17 @@ pub async fn event_handler(
         FullEvent::Message { new_message } => sent::handler(ctx, new_message).await?,
         FullEvent::MessageDelete { channel_id, deleted_message_id, .. } => deleted::handler(ctx, channel_id, deleted_message_id).await?,
         FullEvent::MessageUpdate { event, .. } => edited::handler(ctx, event).await?,
-        FullEvent::GuildMemberAddition { new_member} => welcome::handler(ctx, new_member).await?,
+        FullEvent::GuildMemberAddition { new_member} => member_join::handler(ctx, new_member).await?,
+        FullEvent::GuildMemberRemoval { guild_id, user, member_data_if_available } => {
+            // Los roles automáticos y el captcha pendientes no deben seguir activos si el miembro vuelve a entrar
+            PendingAutoRole::remove_member(*guild_id, user.id).await?;
+            cancel_captcha(&ctx.http, *guild_id, user.id).await?;
+            goodbye::handler(ctx, *guild_id, user, member_data_if_available.as_ref()).await?;
+        }
         FullEvent::ReactionAdd { add_reaction } => reaction_add::handler(ctx, add_reaction).await?,
-        FullEvent::TypingStart { event } => typing_start::handler(event).await?,
+        FullEvent::TypingStart { event } => typing_start::handler(&ctx.http, event).await?,
         FullEvent::InteractionCreate { interaction } => interactions::handler(ctx, interaction, &framework).await?,
+        FullEvent::AutoModActionExecution { execution } => native_automod::handle_execution(ctx, execution).await?,
 
         /*
         serenity::FullEvent::PresenceUpdate { .. } => {
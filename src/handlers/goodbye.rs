@@ -0,0 +1,180 @@
+use std::fs::remove_file;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use serde::{Deserialize, Serialize};
+use serenity::all::{ChannelId, CreateAttachment, CreateMessage, GuildId, Http, Member, Timestamp, User, UserId};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::commands::moderation::welcome::WelcomeText;
+use crate::handlers::misc::raid::is_raid_mode;
+use crate::handlers::welcome::{render_card, DEFAULT_WELCOME_BACKGROUND};
+use crate::utils::CommandResult;
+use crate::utils::config::GuildData;
+use crate::utils::debug::UnwrapResult;
+use crate::utils::template::{format_duration, TemplateContext};
+
+/// Mensaje de despedida por defecto si el servidor no ha establecido uno
+pub const DEFAULT_GOODBYE_MESSAGE: &str = "**{user}** ha abandonado {server} después de {time_in_server}. ¡Hasta pronto!";
+
+/// Tiempo durante el que se recuerda una expulsión hecha por el Bot
+const BOT_REMOVAL_TTL: Duration = Duration::from_secs(60);
+
+/// # Miembros expulsados por el Bot
+///
+/// - Sus salidas no se despiden, ya que son sanciones automáticas (raid, antigüedad o captcha)
+/// - Se guarda el momento de la expulsión para ignorar las marcas antiguas
+static BOT_REMOVALS: LazyLock<DashMap<(GuildId, UserId), Instant>> = LazyLock::new(DashMap::new);
+
+/// # Expulsa a un miembro sin enviar la despedida
+///
+/// - Se marca al miembro antes de expulsarle, porque Discord puede avisar de la salida antes de responder
+/// - Si la expulsión falla, se borra la marca para no ocultar una salida voluntaria posterior
+pub async fn kick_without_goodbye(http: &Http, guild_id: GuildId, user_id: UserId, reason: &str) -> serenity::Result<()> {
+    BOT_REMOVALS.insert((guild_id, user_id), Instant::now());
+    let result = guild_id.kick_with_reason(http, user_id, reason).await;
+    if result.is_err() {
+        BOT_REMOVALS.remove(&(guild_id, user_id));
+    }
+
+    result
+}
+
+/// Comprueba si el Bot acaba de expulsar al miembro y borra la marca
+fn take_bot_removal(guild_id: GuildId, user_id: UserId) -> bool {
+    BOT_REMOVALS
+        .remove(&(guild_id, user_id))
+        .is_some_and(|(_, removed_at)| removed_at.elapsed() < BOT_REMOVAL_TTL)
+}
+
+/// # Fecha de entrada de un miembro al servidor
+///
+/// - Discord solo envía la fecha de entrada al salir si el miembro estaba en la caché,
+///   por lo que se guarda al entrar para calcular el tiempo en el servidor
+/// - `joined_at` es el momento (Unix) en el que el miembro entró
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MemberJoin {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub joined_at: i64,
+}
+
+impl MemberJoin {
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        // Se elimina antes de guardarlo para no duplicar la entrada si el miembro vuelve a entrar
+        Self::remove_member(self.guild_id, self.user_id).await?;
+        let _created: Vec<Self> = DB
+            .create("member_join")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_member(guild_id: GuildId, user_id: UserId) -> SurrealResult<Option<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM member_join WHERE guild_id = $guild_id AND user_id = $user_id";
+        let existing_data: Option<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?
+            .take(0)?;
+
+        Ok(existing_data)
+    }
+
+    pub async fn remove_member(guild_id: GuildId, user_id: UserId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE member_join WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// # Calcula el tiempo que un miembro ha estado en el servidor
+///
+/// - La fecha de entrada puede no estar disponible si el miembro entró antes de que se guardara
+pub fn time_in_server(joined_at: Option<Timestamp>) -> String {
+    joined_at.map_or_else(|| "un tiempo desconocido".to_string(), |joined_at| {
+        format_duration(Timestamp::now().unix_timestamp() - joined_at.unix_timestamp())
+    })
+}
+
+/// # Esta función maneja la salida de un miembro del servidor
+///
+/// - Se envía el mensaje de despedida al canal configurado con el tiempo que estuvo en el servidor
+/// - No se despide a los miembros expulsados por el Bot ni a los que salen durante un raid
+/// - Si Discord no envía la fecha de entrada, se usa la que se guardó cuando el miembro entró
+/// - Si la tarjeta está activada, se adjunta con el mismo fondo y avatar que la de bienvenida
+///   y las líneas de texto marcadas como de despedida
+pub async fn handler(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    user: &User,
+    member: Option<&Member>,
+) -> CommandResult {
+    // La entrada guardada se borra siempre, aunque la despedida esté desactivada
+    let stored_join = MemberJoin::get_member(guild_id, user.id).await?;
+    MemberJoin::remove_member(guild_id, user.id).await?;
+    let joined_at = member
+        .and_then(|member| member.joined_at)
+        .or_else(|| stored_join.and_then(|join| Timestamp::from_unix_timestamp(join.joined_at).ok()));
+
+    // Se borra la marca aunque haya un raid, para que no se quede guardada
+    let removed_by_bot = take_bot_removal(guild_id, user.id);
+    if removed_by_bot || is_raid_mode(guild_id) { return Ok(()) }
+
+    let Some(guild_data) = GuildData::verify_data(guild_id).await? else { return Ok(()) };
+    let goodbye = guild_data.goodbye;
+    if goodbye.enabled.as_deref() != Some("true") { return Ok(()) }
+
+    let Some(channel_id) = goodbye.channel.and_then(|channel| channel.parse::<ChannelId>().ok()) else { return Ok(()) };
+    let template = goodbye.message.unwrap_or_else(|| DEFAULT_GOODBYE_MESSAGE.to_string());
+    let template_context = TemplateContext::for_user(ctx, guild_id, user)
+        .channel_id(channel_id)
+        .time_in_server(time_in_server(joined_at));
+
+    let goodbye_message = template_context.render(&template);
+    let message = CreateMessage::default().content(goodbye_message);
+
+    if goodbye.card.as_deref() != Some("true") {
+        channel_id.send_message(&ctx.http, message).await?;
+        return Ok(())
+    }
+
+    let file = render_goodbye_card(guild_id, user, &template_context).await?;
+    let attachment = CreateAttachment::path(&file).await?;
+    channel_id.send_message(&ctx.http, message.add_file(attachment)).await?;
+
+    // Borrar la imágen generada después de usarla
+    remove_file(file)?;
+
+    Ok(())
+}
+
+/// # Genera la tarjeta de despedida de un usuario
+///
+/// - Devuelve la ruta de la imagen generada, que se debe borrar después de enviarla
+pub async fn render_goodbye_card(guild_id: GuildId, user: &User, template_context: &TemplateContext) -> UnwrapResult<String> {
+    let card = GuildData::verify_data(guild_id).await?
+        .map(|data| data.welcome_card)
+        .unwrap_or_default();
+
+    let texts = WelcomeText::get_texts(guild_id).await?
+        .into_iter()
+        .filter(|line| line.goodbye)
+        .collect::<Vec<_>>();
+
+    let output_path = format!("/tmp/{}_goodbye.png", user.id);
+    render_card(&card, &texts, template_context, user, DEFAULT_WELCOME_BACKGROUND, &output_path).await?;
+
+    Ok(output_path)
+}
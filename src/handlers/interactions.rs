@@ -4,23 +4,33 @@ use serenity::all::{CacheHttp, ComponentInteraction, ComponentInteractionDataKin
 use crate::commands::info::help::FOOTER_URL;
 use crate::debug;
 use crate::handlers::misc::buttons::{ButtonAction, handle_action, handle_and_update};
+use crate::handlers::misc::captcha::{self, CAPTCHA_BUTTON_PREFIX};
 use crate::utils::{CommandResult, Data, Error};
 use crate::utils::debug::IntoUnwrapResult;
 
-/// # Esta función maneja las interacciones de botones
+/// # Esta función maneja las interacciones de botones y formularios
 /// - `mc`: La interacción de componente
 /// - `ButtonAction`: Enumeración de acciones de botones
 /// - `help_action()`: Edita el menú de ayuda con los comandos del Bot filtrados por categoría
+/// - Los botones y formularios del captcha llevan el id del miembro, por lo que se revisan por prefijo
 pub async fn handler(
     ctx: &Context,
     interaction: &Interaction,
     framework: &FrameworkContext<'_, Data, Error>
 ) -> CommandResult {
+    if let Some(modal) = interaction.as_modal_submit() {
+        return captcha::handle_answer(ctx, modal).await
+    }
+
     let Some(mc) = interaction.as_message_component() else { return Ok(()) };
     let guild_id = mc.guild_id.into_result()?;
     let custom_id = mc.data.custom_id.as_str();
     debug!("Button pressed: {custom_id}");
 
+    if let Some(user_id) = custom_id.strip_prefix(CAPTCHA_BUTTON_PREFIX) {
+        return captcha::handle_button(ctx, mc, user_id).await
+    }
+
     match ButtonAction::from(custom_id) {
         ButtonAction::HelpMenu => help_action(ctx, mc, framework).await?,
         ButtonAction::Close => ctx.http().delete_message(mc.channel_id, mc.message.id, None).await?,
@@ -0,0 +1,68 @@
+use poise::serenity_prelude as serenity;
+use serenity::all::Timestamp;
+
+use crate::handlers::goodbye::MemberJoin;
+use crate::handlers::welcome;
+use crate::handlers::misc::account_age::check_account_age;
+use crate::handlers::misc::auto_role::assign_auto_roles;
+use crate::handlers::misc::captcha::start_captcha;
+use crate::handlers::misc::punishment::reapply_quarantine;
+use crate::handlers::misc::raid::check_raid;
+use crate::utils::CommandResult;
+
+/// # Resultado de una revisión de entrada
+///
+/// - `Restricted`: se actuó sobre el miembro sin expulsarlo, por lo que no recibe la bienvenida ni los roles automáticos
+/// - `Kicked`: el miembro fue expulsado y termina el flujo de entrada
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinCheck {
+    Allowed,
+    Restricted,
+    Kicked,
+}
+
+/// # Esta función maneja la llegada de un nuevo miembro
+///
+/// - Se guarda la fecha de entrada para calcular el tiempo en el servidor cuando el miembro salga
+/// - Los miembros que salieron durante una cuarentena vuelven a ella sin pasar por el resto de revisiones
+/// - Después se revisa si el servidor está sufriendo un raid y la antigüedad de la cuenta
+/// - Durante un raid se omite la tarjeta de bienvenida para no llenar el canal de imágenes
+/// - Las cuentas demasiado nuevas tampoco reciben la tarjeta de bienvenida ni los roles automáticos
+/// - Solo una expulsión termina el flujo: los miembros restringidos también deben resolver el captcha
+/// - Los bots los añade un administrador, por lo que solo reciben sus roles automáticos
+/// - Con la verificación activada, los roles automáticos y la tarjeta de bienvenida llegan al resolver el captcha
+pub async fn handler(
+    ctx: &serenity::Context,
+    new_member: &serenity::Member,
+) -> CommandResult {
+    let joined_at = new_member.joined_at.unwrap_or_else(Timestamp::now).unix_timestamp();
+    let member_join = MemberJoin { guild_id: new_member.guild_id, user_id: new_member.user.id, joined_at };
+    member_join.save_to_db().await?;
+
+    if new_member.user.bot {
+        return assign_auto_roles(&ctx.http, new_member).await
+    }
+
+    if reapply_quarantine(&ctx.http, new_member).await? {
+        return Ok(())
+    }
+
+    let raid = check_raid(ctx, new_member).await?;
+    if raid == JoinCheck::Kicked {
+        return Ok(())
+    }
+
+    let account_age = check_account_age(ctx, new_member).await?;
+    if account_age == JoinCheck::Kicked {
+        return Ok(())
+    }
+
+    let restricted = raid == JoinCheck::Restricted || account_age == JoinCheck::Restricted;
+    if start_captcha(ctx, new_member, restricted).await? || restricted {
+        return Ok(())
+    }
+
+    assign_auto_roles(&ctx.http, new_member).await?;
+
+    welcome::handler(ctx, new_member).await
+}
@@ -3,8 +3,9 @@ use reqwest::Url;
 use serenity::all::{ChannelId, MessageId};
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
-use crate::location;
+use crate::{location, log_handle};
 
+use crate::handlers::misc::ghost_ping;
 use crate::utils::CommandResult;
 use crate::utils::MessageData;
 use crate::utils::config::GuildData;
@@ -28,6 +29,12 @@ pub async fn handler(ctx: &serenity::Context, channel_id: &ChannelId, deleted_me
 
     // Obtener el canal de logs de la base de datos
     let result_database = database_message.guild_id.unwrap_log(location!())?;
+
+    // Un error al detectar el ghost ping no debe impedir que se registre el mensaje borrado
+    if let Err(why) = ghost_ping::handle_deleted(ctx, result_database, &database_message).await {
+        log_handle!("Error al detectar un ghost ping: {why}");
+    }
+
     let log_channel = GuildData::verify_data(result_database).await?
         .into_result()?
         .channels
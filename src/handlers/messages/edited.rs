@@ -1,9 +1,13 @@
 use poise::serenity_prelude as serenity;
 use serenity::all::{ChannelId, MessageUpdateEvent, RoleId, UserId};
 
-use crate::{debug, location, match_handle};
+use crate::{debug, location, log_handle, match_handle};
 use crate::utils::CommandResult;
+use crate::commands::moderation::filter::FilterRule;
+use crate::handlers::misc::automod::check_automod;
 use crate::handlers::misc::forbidden_mentions::{handle_forbidden_role, handle_forbidden_user};
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::word_filter::{check_word_filter, strictest_match};
 use crate::utils::MessageData;
 use crate::utils::config::GuildData;
 use crate::utils::debug::{IntoUnwrapResult, UnwrapLog};
@@ -24,6 +28,26 @@ pub async fn handler(ctx: &serenity::Context, event: &MessageUpdateEvent) -> Com
     };
     
     if old_content == new_content { return Ok(()) }
+    if let Err(why) = ghost_ping::handle_edited(ctx, guild_id, &database_message, new_content).await {
+        log_handle!("Error al detectar un ghost ping: {why}");
+    }
+
+    // Se guarda el contenido nuevo para que las siguientes ediciones y el borrado lo comparen con él
+    database_message.update_content(new_content).await?;
+
+    // El filtro de palabras también se aplica al contenido editado
+    // Solo se obtiene el mensaje completo si alguna regla coincide
+    let rules = FilterRule::get_rules(guild_id).await?;
+    if strictest_match(&rules, new_content).is_some() {
+        let admin_role_id = GuildData::verify_data(guild_id).await?
+            .unwrap_log(location!())?
+            .admins
+            .role;
+
+        let message = ctx.http.get_message(database_message.channel_id, message_id).await?;
+        if check_word_filter(ctx, &message, guild_id, &admin_role_id).await? { return Ok(()) }
+    }
+
     let log_channel = GuildData::verify_data(guild_id).await?
         .unwrap_log(location!())?
         .channels
@@ -33,6 +57,16 @@ pub async fn handler(ctx: &serenity::Context, event: &MessageUpdateEvent) -> Com
     
     let message_content = format!("\n**Antes:** \n> {old_content}\n**Después:** \n> {new_content}");
 
+    // Con las reglas de moderación automática activadas, sustituyen a las menciones prohibidas
+    let guild_data = GuildData::verify_data(guild_id).await?.unwrap_log(location!())?;
+    if guild_data.automod.enabled.as_deref() == Some("true") {
+        let message = ctx.http.get_message(database_message.channel_id, message_id).await?;
+        if check_automod(ctx, &message, guild_id, &guild_data.admins.role).await? { return Ok(()) }
+        edit_message_embed(ctx, guild_id, log_channel, &database_message.channel_id, database_message.author_id, &message_content).await?;
+
+        return Ok(())
+    }
+
     let mention = event.mentions.as_ref().into_result()?;
     let first = mention.first();
 
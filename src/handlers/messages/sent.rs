@@ -7,9 +7,17 @@ use serenity::all::{EmojiId, GuildId, Message, ReactionType, RoleId, UserId};
 use crate::{DB, location};
 use crate::utils::CommandResult;
 use crate::handlers::misc::attachment_case::attachment_handler;
+use crate::handlers::misc::automod::{check_automod, handles_link_spam};
 use crate::handlers::misc::everyone_case::handle_everyone;
+use crate::handlers::misc::flood::check_flood;
 use crate::handlers::misc::forbidden_mentions::{handle_forbidden_role, handle_forbidden_user};
+use crate::handlers::misc::image_spam::check_image_spam;
+use crate::handlers::misc::invite_filter::check_invites;
+use crate::handlers::misc::link_filter::check_links;
 use crate::handlers::misc::link_spam_handler::{extract_link, spam_checker};
+use crate::handlers::misc::mass_mention::check_mass_mention;
+use crate::handlers::misc::text_filter::check_text_filter;
+use crate::handlers::misc::word_filter::check_word_filter;
 use crate::utils::MessageData;
 use crate::utils::config::GuildData;
 use crate::utils::debug::{IntoUnwrapResult, UnwrapLog};
@@ -18,9 +26,17 @@ use crate::utils::debug::{IntoUnwrapResult, UnwrapLog};
 ///
 /// ## Funciones relacionadas:
 /// - manejo de archivos adjuntos
+/// - manejo de spam de imágenes en varios canales
+/// - manejo de las reglas de moderación automática
 /// - manejo de menciones a roles y usuarios prohibidos
 /// - manejo de menciones a @everyone y @here
+/// - manejo de menciones masivas
+/// - manejo del filtro de palabras
+/// - manejo de mayúsculas, emojis y texto zalgo
+/// - manejo de invitaciones a otros servidores
+/// - manejo de dominios permitidos y bloqueados
 /// - manejo de spam de links
+/// - manejo de flood de mensajes
 /// - guardar el mensaje en la base de datos
 pub async fn handler(ctx: &serenity::Context, new_message: &Message) -> CommandResult {
     // Crear un objeto Arc<String> con el contenido del mensaje para utilizar cheap cloning (clonación barata)
@@ -55,9 +71,8 @@ pub async fn handler(ctx: &serenity::Context, new_message: &Message) -> CommandR
         println!("Error handling attachment: {why:?} {}", Location::caller());
     }
     
-    // Si el mensaje no tiene contenido, es probable que sea un sticker o una imágen
-    // En este caso, es mejor salir de la función en lugar de guardar en los logs
-    if message_content.is_empty() {
+    // Las imágenes se revisan antes de salir por contenido vacío, ya que suelen enviarse sin texto
+    if check_image_spam(ctx, new_message, guild_id, &admin_role_id, time).await? {
         return Ok(())
     }
 
@@ -69,20 +84,81 @@ pub async fn handler(ctx: &serenity::Context, new_message: &Message) -> CommandR
         new_message.guild_id,
     );
 
+    // Las reglas de moderación automática se revisan antes de salir por contenido vacío,
+    // ya que pueden depender de los archivos adjuntos
+    if check_automod(ctx, new_message, guild_id, &admin_role_id).await? {
+        if !message_content.is_empty() {
+            let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
+        }
+
+        return Ok(())
+    }
+
+    // Si el mensaje no tiene contenido, es probable que sea un sticker o una imágen
+    // En este caso, es mejor salir de la función en lugar de guardar en los logs
+    if message_content.is_empty() {
+        return Ok(())
+    }
+
+    // Las palabras prohibidas se revisan antes que los enlaces y el spam
+    if check_word_filter(ctx, new_message, guild_id, &admin_role_id).await? {
+        let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
+        return Ok(())
+    }
+
+    // Mayúsculas, emojis y texto zalgo
+    if check_text_filter(ctx, new_message, guild_id, &admin_role_id).await? {
+        let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
+        return Ok(())
+    }
+
+    // Las invitaciones a otros servidores se borran antes de revisar el resto de enlaces
+    if check_invites(ctx, new_message, guild_id, &admin_role_id).await? {
+        let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
+        return Ok(())
+    }
+
+    // Los enlaces bloqueados se borran antes de revisar el spam
+    if check_links(ctx, new_message, guild_id, &admin_role_id).await? {
+        let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
+        return Ok(())
+    }
+
     // Extraer el link del mensaje si existe
-    if extract_link(&message_content).is_some() {
+    // Con una regla de spam de enlaces activa, el spam ya se ha revisado con las reglas de moderación automática
+    if extract_link(&message_content).is_some() && !handles_link_spam(guild_id).await? {
         let channel_id = new_message.channel_id;
         spam_checker(&message_content, channel_id, &admin_role_id, ctx, time, new_message, guild_id).await?;
     }
 
-    if user_id.is_some() {
+    // El flood se revisa junto al spam: muchos mensajes seguidos o mensajes parecidos en poco tiempo
+    if check_flood(ctx, new_message, guild_id, &admin_role_id).await? {
+        let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
+        return Ok(())
+    }
+
+    // Las menciones masivas se sancionan antes de revisar las menciones prohibidas
+    if check_mass_mention(ctx, new_message, guild_id, &admin_role_id, time).await? {
+        let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
+        return Ok(())
+    }
+
+    // Con las reglas de moderación automática activadas, las menciones prohibidas y @everyone
+    // se manejan como reglas por defecto en lugar de con los módulos anteriores
+    let automod_enabled = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .automod
+        .enabled
+        .is_some_and(|enabled| enabled == "true");
+
+    if user_id.is_some() && !automod_enabled {
         handle_user_id(ctx, new_message, guild_id, &data, user_id).await?;
     }
 
     // @everyone no tiene id, por lo que no es necesario el <@id>
     // Si bien hay un método para comprobar si se menciona @everyone o @here, este método devuelve
     // `false` en servidores donde @everyone y @here están deshabilitados
-    if message_content.contains("@everyone") || message_content.contains("@here") {
+    if !automod_enabled && (message_content.contains("@everyone") || message_content.contains("@here")) {
         let _created: Vec<MessageData> = DB.create("messages").content(&data).await?;
         handle_everyone(admin_role_id, &mut member, ctx, time, new_message).await?;
 
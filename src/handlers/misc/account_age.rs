@@ -0,0 +1,117 @@
+use poise::ChoiceParameter;
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, Member, RoleId, Timestamp};
+
+use crate::log_handle;
+use crate::commands::moderation::setters::set_account_age::AccountAgeAction;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::goodbye::kick_without_goodbye;
+use crate::handlers::member_join::JoinCheck;
+use crate::handlers::misc::dry_run;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+use crate::utils::template::format_duration;
+
+/// # Esta función revisa la antigüedad de la cuenta de un miembro nuevo
+///
+/// - Si la cuenta es más nueva que el mínimo del servidor, se aplica la acción configurada
+/// - La decisión se registra en el canal de Logs
+/// - El miembro queda restringido si se actuó sobre la cuenta, para omitir la tarjeta de bienvenida,
+///   o expulsado si esa es la acción configurada
+pub async fn check_account_age(ctx: &serenity::Context, member: &Member) -> Result<JoinCheck, Error> {
+    let guild_id = member.guild_id;
+    let account_age = GuildData::verify_data(guild_id).await?
+        .map(|data| data.account_age)
+        .unwrap_or_default();
+
+    let min_hours = account_age.min_hours
+        .and_then(|hours| hours.parse::<i64>().ok())
+        .unwrap_or_default();
+
+    if min_hours == 0 { return Ok(JoinCheck::Allowed) }
+
+    let age = Timestamp::now().unix_timestamp() - member.user.id.created_at().unix_timestamp();
+    if age >= min_hours * 3600 { return Ok(JoinCheck::Allowed) }
+
+    let action = account_age.action.as_deref().map_or(AccountAgeAction::Dm, AccountAgeAction::from);
+    let description = format!(
+        "{} a <@{}> porque su cuenta tiene {} de antigüedad (mínimo: {min_hours} horas)",
+        action.name(),
+        member.user.id,
+        format_duration(age)
+    );
+
+    // En modo de prueba no se toma ninguna acción, así que el miembro recibe la bienvenida normal
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::AccountAge, &description).await? { return Ok(JoinCheck::Allowed) }
+
+    match action {
+        AccountAgeAction::Quarantine => {
+            let role_id = account_age.role.into_result()?.parse::<RoleId>()?;
+            member.add_role(&ctx.http, role_id).await?;
+        }
+        AccountAgeAction::Dm => notify_member(ctx, member, min_hours).await,
+        AccountAgeAction::Kick => {
+            // El mensaje privado se envía antes de expulsar, porque después ya no comparte servidor con el Bot
+            notify_member(ctx, member, min_hours).await;
+            kick_without_goodbye(&ctx.http, member.guild_id, member.user.id, "Cuenta demasiado nueva").await?;
+        }
+    }
+
+    log_account_age(ctx, member, &action, age).await?;
+
+    if action == AccountAgeAction::Kick { return Ok(JoinCheck::Kicked) }
+
+    Ok(JoinCheck::Restricted)
+}
+
+/// # Envía un mensaje privado explicando por qué no puede participar en el servidor
+///
+/// - Si el miembro tiene los mensajes privados cerrados, solo se registra el error
+async fn notify_member(ctx: &serenity::Context, member: &Member, min_hours: i64) {
+    let server = member.guild_id
+        .name(&ctx.cache)
+        .unwrap_or_else(|| "el servidor".to_string());
+
+    let content = format!(
+        "Tu cuenta es demasiado nueva para participar en **{server}**. \
+        Se requiere una antigüedad mínima de {min_hours} horas."
+    );
+
+    if let Err(why) = member.user.direct_message(ctx, CreateMessage::default().content(content)).await {
+        log_handle!("No se pudo enviar el mensaje privado a {}: {why}", member.user.id);
+    }
+}
+
+/// # Esta función envía un `Embed` al canal de Logs con la cuenta demasiado nueva
+async fn log_account_age(
+    ctx: &serenity::Context,
+    member: &Member,
+    action: &AccountAgeAction,
+    age: i64,
+) -> UnwrapResult<()> {
+    let log_channel = GuildData::verify_data(member.guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let user = &member.user;
+    let embed = CreateEmbed::default()
+        .title("🆕 Cuenta demasiado nueva")
+        .author(CreateEmbedAuthor::new(user.name.clone())
+            .icon_url(user.face()))
+        .description(format!(
+            "El usuario <@{}> ha entrado con una cuenta creada hace {}.\n**Creada:** <t:{}:F>\n**Acción:** {}",
+            user.id,
+            format_duration(age),
+            user.id.created_at().unix_timestamp(),
+            action.name()
+        ))
+        .color(0x00ff_a500);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use serenity::all::{GuildId, Http, Member, RoleId, Timestamp, UserId};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::commands::moderation::auto_role::AutoRole;
+use crate::handlers::misc::punishment::QuarantineEntry;
+use crate::log_handle;
+use crate::utils::Error;
+use crate::utils::scheduler::ScheduledEntry;
+
+/// # Asignación de un rol automático que espera su retraso
+///
+/// - `at` es el momento (Unix) en el que se asigna el rol
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PendingAutoRole {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub role_id: RoleId,
+    pub at: i64,
+}
+
+impl PendingAutoRole {
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("pending_auto_role")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_entry(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE pending_auto_role WHERE guild_id = $guild_id AND user_id = $user_id AND role_id = $role_id";
+        DB.query(sql_query)
+            .bind(("guild_id", self.guild_id))
+            .bind(("user_id", self.user_id))
+            .bind(("role_id", self.role_id))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_member(guild_id: GuildId, user_id: UserId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE pending_auto_role WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_role(guild_id: GuildId, role_id: RoleId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE pending_auto_role WHERE guild_id = $guild_id AND role_id = $role_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("role_id", role_id))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// # Asigna los roles automáticos a un miembro nuevo
+///
+/// - Los bots solo reciben los roles marcados para bots, y los usuarios solo el resto
+/// - Los roles sin retraso se asignan en el momento; los demás se guardan en la base de datos
+///   para que el retraso sobreviva a los reinicios del Bot
+/// - Si la cuenta no cumple la antigüedad mínima del rol, no se asigna
+/// - Un rol que no se pueda asignar no impide asignar los demás
+pub async fn assign_auto_roles(http: &Http, member: &Member) -> Result<(), Error> {
+    let guild_id = member.guild_id;
+    let user_id = member.user.id;
+    let now = Timestamp::now().unix_timestamp();
+    let account_age = now - user_id.created_at().unix_timestamp();
+
+    let roles = AutoRole::get_roles(guild_id).await?
+        .into_iter()
+        .filter(|role| role.bots == member.user.bot)
+        .filter(|role| role.min_account_age.map_or(true, |days| account_age >= days * 86400));
+
+    for role in roles {
+        if role.delay > 0 {
+            let pending = PendingAutoRole { guild_id, user_id, role_id: role.role_id, at: now + role.delay };
+            pending.save_to_db().await?;
+            continue;
+        }
+
+        if let Err(why) = member.add_role(http, role.role_id).await {
+            log_handle!("No se pudo asignar el rol automático {} a {user_id}: {why}", role.role_id);
+        }
+    }
+
+    Ok(())
+}
+
+/// # Asigna un rol automático pendiente
+///
+/// - Si el miembro ya no está en el servidor o el rol se ha borrado, solo se borra el registro
+/// - Si el miembro está en cuarentena, la asignación se pospone hasta que termine
+async fn apply_pending(http: &Http, entry: &PendingAutoRole) -> Result<(), Error> {
+    // El registro se borra aunque falle la asignación, para no reintentarla en cada revisión
+    entry.remove_entry().await?;
+
+    if let Some(quarantine) = QuarantineEntry::get_entry(entry.guild_id, entry.user_id).await? {
+        let postponed = PendingAutoRole { at: quarantine.until, ..entry.clone() };
+        postponed.save_to_db().await?;
+        return Ok(())
+    }
+
+    let Ok(member) = entry.guild_id.member(http, entry.user_id).await else { return Ok(()) };
+    let guild_roles = entry.guild_id.roles(http).await?;
+    if guild_roles.contains_key(&entry.role_id) && !member.roles.contains(&entry.role_id) {
+        member.add_role(http, entry.role_id).await?;
+    }
+
+    Ok(())
+}
+
+impl ScheduledEntry for PendingAutoRole {
+    const FETCH_ERROR: &'static str = "No se pudieron obtener los roles automáticos pendientes";
+
+    async fn get_due(now: i64) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM pending_auto_role WHERE at <= $now";
+        let entries: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("now", now))
+            .await?
+            .take(0)?;
+
+        Ok(entries)
+    }
+
+    async fn run(&self, http: &Http) -> Result<(), Error> {
+        apply_pending(http, self).await
+    }
+
+    fn error_message(&self) -> String {
+        format!("No se pudo asignar el rol automático {} a {}", self.role_id, self.user_id)
+    }
+}
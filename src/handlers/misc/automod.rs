@@ -0,0 +1,414 @@
+use std::fmt::Write;
+use std::sync::Arc;
+
+use poise::ChoiceParameter;
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, GuildId, Member, Message, RoleId, Timestamp, UserId};
+
+use crate::commands::moderation::automod::{AutomodAction, AutomodRule, AutomodTrigger};
+use crate::commands::moderation::setters::set_availability::AvailabilityWindow;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::commands::moderation::setters::set_forbidden_exception::ForbiddenException;
+use crate::commands::moderation::setters::set_reply_ping_policy::ReplyPingPolicy;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::forbidden_mentions::{is_reply_ping, DEFAULT_WARN_MESSAGE};
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::link_filter::{domain_matches, extract_domain};
+use crate::handlers::misc::link_spam_handler::{delete_spam_messages, extract_link, extract_links, record_link_spam};
+use crate::handlers::misc::punishment::silence_member;
+use crate::handlers::misc::warns::{warn_and_delete, MAX_WARNS};
+use crate::handlers::misc::word_filter::{compile_rule_regex, normalize_text};
+use crate::utils::{Error, Warns};
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+use crate::utils::template::{format_duration, upgrade_legacy, TemplateContext};
+
+/// Mensaje de advertencia de las reglas que no son de menciones
+pub const DEFAULT_AUTOMOD_WARN_MESSAGE: &str = "{user} Tu mensaje infringe las reglas de moderación del servidor";
+
+/// # Objetivo de una regla de menciones
+enum MentionTarget {
+    User(UserId),
+    Role(RoleId),
+    /// Un id sin formato puede ser tanto de un usuario como de un rol
+    Any(u64),
+    Everyone,
+}
+
+impl MentionTarget {
+    fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if matches!(value, "everyone" | "here" | "@everyone" | "@here") { return Some(Self::Everyone) }
+
+        if let Some(role) = value.strip_prefix("<@&").and_then(|value| value.strip_suffix('>')) {
+            return role.parse::<RoleId>().ok().map(Self::Role)
+        }
+
+        if let Some(user) = value.strip_prefix("<@").and_then(|value| value.strip_suffix('>')) {
+            return user.trim_start_matches('!').parse::<UserId>().ok().map(Self::User)
+        }
+
+        value.parse::<u64>().ok().filter(|id| *id != 0).map(Self::Any)
+    }
+}
+
+/// # Resultado de comparar el disparador de una regla con el mensaje
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TriggerMatch {
+    None,
+    Matched,
+    /// Solo coincide por responder con `@` a un usuario, y la política del servidor es advertir sin sancionar
+    ReplyPingWarn,
+}
+
+impl From<bool> for TriggerMatch {
+    fn from(matched: bool) -> Self {
+        if matched { Self::Matched } else { Self::None }
+    }
+}
+
+/// # Comprueba si un usuario mencionado ha pedido que se le pueda mencionar
+///
+/// - Se respetan las excepciones y las ventanas de disponibilidad del usuario
+async fn allows_mentions(user_id: UserId, guild_id: GuildId) -> Result<bool, Error> {
+    if ForbiddenException::have_exception(user_id).await?.unwrap_or(false) { return Ok(true) }
+
+    Ok(AvailabilityWindow::is_available(user_id, guild_id).await?)
+}
+
+/// # Obtiene los roles de los usuarios mencionados
+///
+/// - Los eventos del Gateway incluyen los roles; si no están, se obtiene el miembro
+async fn mentioned_roles(ctx: &serenity::Context, message: &Message, guild_id: GuildId) -> Vec<RoleId> {
+    let mut roles = Vec::new();
+    for user in &message.mentions {
+        match &user.member {
+            Some(member) => roles.extend(member.roles.iter().copied()),
+            None => if let Ok(member) = guild_id.member(&ctx.http, user.id).await {
+                roles.extend(member.roles);
+            },
+        }
+    }
+
+    roles
+}
+
+/// # Comprueba si el mensaje menciona a alguno de los objetivos de la regla
+///
+/// - Un rol se considera mencionado si se menciona el rol o a un miembro que lo tiene
+/// - Las respuestas con `@` a un usuario siguen la política de respuestas del servidor (`set_reply_ping_policy`)
+async fn mention_matches(
+    ctx: &serenity::Context,
+    rule: &AutomodRule,
+    message: &Message,
+    guild_id: GuildId,
+    reply_ping_policy: ReplyPingPolicy,
+) -> Result<TriggerMatch, Error> {
+    let targets = rule.value.split(',').filter_map(MentionTarget::parse).collect::<Vec<_>>();
+    let mut roles = None;
+    let mut reply_ping = false;
+
+    for target in targets {
+        let (user_id, role_id) = match target {
+            // `mention_everyone` es `false` en los servidores donde la mención está deshabilitada
+            MentionTarget::Everyone => {
+                let everyone = message.mention_everyone
+                    || message.content.contains("@everyone")
+                    || message.content.contains("@here");
+
+                if everyone { return Ok(TriggerMatch::Matched) }
+                continue
+            }
+            MentionTarget::User(user_id) => (Some(user_id), None),
+            MentionTarget::Role(role_id) => (None, Some(role_id)),
+            MentionTarget::Any(id) => (Some(UserId::new(id)), Some(RoleId::new(id))),
+        };
+
+        if let Some(user_id) = user_id {
+            let mentioned = user_id != message.author.id && message.mentions_user_id(user_id);
+            if mentioned && !allows_mentions(user_id, guild_id).await? {
+                if !is_reply_ping(message, user_id) { return Ok(TriggerMatch::Matched) }
+                match reply_ping_policy {
+                    ReplyPingPolicy::Escalate => return Ok(TriggerMatch::Matched),
+                    ReplyPingPolicy::Warn => reply_ping = true,
+                    ReplyPingPolicy::Allow => {}
+                }
+            }
+        }
+
+        if let Some(role_id) = role_id {
+            if message.mention_roles.contains(&role_id) { return Ok(TriggerMatch::Matched) }
+            if roles.is_none() { roles = Some(mentioned_roles(ctx, message, guild_id).await) }
+            if roles.as_ref().is_some_and(|roles| roles.contains(&role_id)) { return Ok(TriggerMatch::Matched) }
+        }
+    }
+
+    Ok(if reply_ping { TriggerMatch::ReplyPingWarn } else { TriggerMatch::None })
+}
+
+/// # Comprueba si el mensaje contiene un enlace a alguno de los dominios de la regla
+///
+/// - Sin dominios, cualquier enlace dispara la regla
+fn link_matches(rule: &AutomodRule, content: &str) -> bool {
+    let patterns = rule.value
+        .split(',')
+        .map(|domain| domain.trim().to_lowercase())
+        .filter(|domain| !domain.is_empty())
+        .collect::<Vec<_>>();
+
+    extract_links(content)
+        .iter()
+        .filter_map(|link| extract_domain(link))
+        .any(|host| patterns.is_empty() || patterns.iter().any(|pattern| domain_matches(&host, pattern)))
+}
+
+/// # Comprueba si el mensaje tiene un archivo adjunto de alguno de los tipos de la regla
+///
+/// - Cada tipo puede ser un tipo MIME (`image/`, `video/mp4`) o una extensión (`.exe`, `zip`)
+/// - Sin tipos, cualquier archivo adjunto dispara la regla
+fn attachment_matches(rule: &AutomodRule, message: &Message) -> bool {
+    let kinds = rule.value
+        .split(',')
+        .map(|kind| kind.trim().to_lowercase())
+        .filter(|kind| !kind.is_empty())
+        .collect::<Vec<_>>();
+
+    message.attachments.iter().any(|attachment| {
+        if kinds.is_empty() { return true }
+        let filename = attachment.filename.to_lowercase();
+        let content_type = attachment.content_type.as_deref().unwrap_or_default().to_lowercase();
+
+        kinds.iter().any(|kind| {
+            if kind.contains('/') { return content_type.starts_with(kind.as_str()) }
+            filename.ends_with(&format!(".{}", kind.trim_start_matches('.')))
+        })
+    })
+}
+
+/// # Comprueba si el disparador de la regla coincide con el mensaje
+///
+/// - `link_spam` indica si el rastreador de spam ya ha considerado el mensaje como spam
+async fn trigger_matches(
+    ctx: &serenity::Context,
+    rule: &AutomodRule,
+    message: &Message,
+    guild_id: GuildId,
+    reply_ping_policy: ReplyPingPolicy,
+    link_spam: bool,
+) -> Result<TriggerMatch, Error> {
+    let matched = match rule.trigger {
+        AutomodTrigger::Mention => return mention_matches(ctx, rule, message, guild_id, reply_ping_policy).await,
+        AutomodTrigger::Regex => compile_rule_regex(&rule.value)
+            .is_ok_and(|regex| regex.is_match(&normalize_text(&message.content))),
+        AutomodTrigger::Link => link_matches(rule, &message.content),
+        AutomodTrigger::Attachment => attachment_matches(rule, message),
+        AutomodTrigger::LinkSpam => link_spam,
+    };
+
+    Ok(TriggerMatch::from(matched))
+}
+
+/// # Comprueba las condiciones y exenciones de la regla
+///
+/// - Las condiciones vacías no limitan la regla
+fn applies_to(rule: &AutomodRule, message: &Message, member: &Member) -> bool {
+    if rule.exempt_channels.contains(&message.channel_id) { return false }
+    if member.roles.iter().any(|role| rule.exempt_roles.contains(role)) { return false }
+    if !rule.channels.is_empty() && !rule.channels.contains(&message.channel_id) { return false }
+    if !rule.roles.is_empty() && !member.roles.iter().any(|role| rule.roles.contains(role)) { return false }
+
+    rule.max_account_age.map_or(true, |max_hours| {
+        let age = Timestamp::now().unix_timestamp() - message.author.id.created_at().unix_timestamp();
+        age < max_hours * 3600
+    })
+}
+
+/// # Esta función evalúa las reglas de moderación automática del servidor
+///
+/// - Solo se evalúan si el motor de reglas está activado
+/// - Las acciones de todas las reglas que coinciden se combinan: el mensaje se borra una sola vez
+///   y se usa el silencio más largo
+/// - Los administradores no se revisan
+/// - Devuelve `true` si el mensaje fue borrado
+pub async fn check_automod(
+    ctx: &serenity::Context,
+    message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+) -> Result<bool, Error> {
+    let Some(guild_data) = GuildData::verify_data(guild_id).await? else { return Ok(false) };
+    if guild_data.automod.enabled.as_deref() != Some("true") { return Ok(false) }
+
+    let reply_ping_policy = guild_data.forbidden.reply_ping
+        .as_deref()
+        .map_or(ReplyPingPolicy::Escalate, ReplyPingPolicy::from);
+
+    let rules = AutomodRule::get_rules(guild_id).await?;
+
+    // El rastreador de spam se actualiza una sola vez por mensaje, aunque haya varias reglas de spam
+    let has_link_spam_rule = rules.iter().any(|rule| rule.trigger == AutomodTrigger::LinkSpam);
+    let link_spam = if has_link_spam_rule && extract_link(&message.content).is_some() {
+        let content = Arc::new(message.content.clone());
+        record_link_spam(&content, message.channel_id, message.author.id, guild_id).await?
+    } else {
+        None
+    };
+
+    let mut triggered = Vec::new();
+    let mut reply_pings = Vec::new();
+    for rule in &rules {
+        match trigger_matches(ctx, rule, message, guild_id, reply_ping_policy, link_spam.is_some()).await? {
+            TriggerMatch::Matched => triggered.push(rule),
+            TriggerMatch::ReplyPingWarn => reply_pings.push(rule),
+            TriggerMatch::None => {}
+        }
+    }
+
+    if triggered.is_empty() && reply_pings.is_empty() { return Ok(false) }
+    let mut member = guild_id.member(&ctx.http, message.author.id).await?;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) { return Ok(false) }
+
+    let matched = triggered.into_iter().filter(|rule| applies_to(rule, message, &member)).collect::<Vec<_>>();
+    if matched.is_empty() {
+        if reply_pings.iter().any(|rule| applies_to(rule, message, &member)) {
+            warn_reply_ping(ctx, message, &member, &guild_data).await?;
+        }
+
+        return Ok(false)
+    }
+
+    let has_action = |action: AutomodAction| matched.iter().any(|rule| rule.actions.contains(&action));
+    let server_timeout = guild_data.time_out.time.as_deref().and_then(|time| time.parse::<i64>().ok());
+    let timeout = matched
+        .iter()
+        .filter(|rule| rule.actions.contains(&AutomodAction::Timeout))
+        .filter_map(|rule| rule.timeout.or(server_timeout))
+        .max();
+
+    let roles = matched
+        .iter()
+        .filter(|rule| rule.actions.contains(&AutomodAction::Role))
+        .filter_map(|rule| rule.role)
+        .collect::<Vec<_>>();
+
+    let names = matched.iter().map(|rule| format!("`{}`", rule.name)).collect::<Vec<_>>().join(", ");
+    let mut action = format!("Aplicar las reglas {names} al mensaje de <@{}> en <#{}>:", message.author.id, message.channel_id);
+    for rule_action in [AutomodAction::Delete, AutomodAction::Warn, AutomodAction::Timeout, AutomodAction::Role, AutomodAction::Log] {
+        if has_action(rule_action) { write!(action, " {}", rule_action.name())?; }
+    }
+
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::Automod, &action).await? { return Ok(false) }
+
+    let warned = has_action(AutomodAction::Warn);
+    if warned {
+        let mention_rule = matched.iter().any(|rule| rule.trigger == AutomodTrigger::Mention);
+        let warn_template = match guild_data.messages.warn {
+            Some(warn) if mention_rule => upgrade_legacy(&warn, "{user} "),
+            _ if mention_rule => DEFAULT_WARN_MESSAGE.to_string(),
+            _ => DEFAULT_AUTOMOD_WARN_MESSAGE.to_string(),
+        };
+
+        let reason = format!("por infringir las reglas {names}");
+        warn_and_delete(ctx, message, guild_id, &warn_template, &reason).await?;
+    }
+
+    if let Some(seconds) = timeout {
+        silence_member(&ctx.http, &mut member, seconds).await?;
+    }
+
+    for role in roles {
+        member.add_role(&ctx.http, role).await?;
+    }
+
+    // El sistema de advertencias ya borra el mensaje
+    let deleted = warned || has_action(AutomodAction::Delete);
+    if deleted && !warned {
+        ghost_ping::ignore_deletion(message.id);
+        message.delete(&ctx.http).await?;
+    }
+
+    // El spam de enlaces también se borra de los demás canales, igual que en el módulo de spam
+    let link_spam_matched = matched.iter().any(|rule| rule.trigger == AutomodTrigger::LinkSpam);
+    if let Some(channel_ids) = link_spam.filter(|_| deleted && link_spam_matched) {
+        let content = Arc::new(message.content.clone());
+        delete_spam_messages(&channel_ids, ctx, message.author.id, content, guild_id).await?;
+    }
+
+    if has_action(AutomodAction::Log) {
+        log_automod_match(ctx, guild_id, message, &matched, timeout).await?;
+    }
+
+    Ok(deleted)
+}
+
+/// # Comprueba si el spam de enlaces lo manejan las reglas de moderación automática
+///
+/// - Así el rastreador de spam no registra dos veces el mismo mensaje
+pub async fn handles_link_spam(guild_id: GuildId) -> Result<bool, Error> {
+    let enabled = GuildData::verify_data(guild_id).await?
+        .is_some_and(|data| data.automod.enabled.as_deref() == Some("true"));
+
+    if !enabled { return Ok(false) }
+
+    Ok(AutomodRule::get_rules(guild_id).await?.iter().any(|rule| rule.trigger == AutomodTrigger::LinkSpam))
+}
+
+/// # Advierte a quien responde con `@` a un usuario protegido
+///
+/// - Igual que la política `Warn` de las menciones prohibidas: no se suman advertencias ni se borra el mensaje
+async fn warn_reply_ping(ctx: &serenity::Context, message: &Message, member: &Member, guild_data: &GuildData) -> Result<(), Error> {
+    let warn_template = guild_data.messages.warn
+        .as_deref()
+        .map_or_else(|| DEFAULT_WARN_MESSAGE.to_string(), |warn| upgrade_legacy(warn, "{user} "));
+
+    let current_warns = Warns::new(message.author.id).get_warns().await?.map_or(0, |warns| warns.warns);
+    let warn_message = TemplateContext::for_member(ctx, member)
+        .channel_id(message.channel_id)
+        .warns(current_warns)
+        .max_warns(MAX_WARNS)
+        .render(&warn_template);
+
+    message.reply(&ctx.http, warn_message).await?;
+
+    Ok(())
+}
+
+/// # Esta función envía un `Embed` al canal de Logs con las reglas que coincidieron
+async fn log_automod_match(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    message: &Message,
+    rules: &[&AutomodRule],
+    timeout: Option<i64>,
+) -> UnwrapResult<()> {
+    let log_channel = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let mut description = format!("El mensaje de <@{}> en <#{}> coincide con:\n", message.author.id, message.channel_id);
+    for rule in rules {
+        writeln!(description, "- {}", rule.describe())?;
+    }
+
+    if let Some(seconds) = timeout {
+        writeln!(description, "**Silencio:** {}", format_duration(seconds))?;
+    }
+
+    write!(description, "**Mensaje:** {}", message.content)?;
+
+    let author = &message.author;
+    let embed = CreateEmbed::default()
+        .title("🤖 Moderación automática")
+        .author(CreateEmbedAuthor::new(author.name.clone())
+            .icon_url(author.face()))
+        .description(description)
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,393 @@
+use std::fs::remove_file;
+use std::ops::Range;
+
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::{draw_filled_circle_mut, draw_line_segment_mut, draw_text_mut};
+use poise::serenity_prelude as serenity;
+use rand::Rng;
+use rusttype::{Font, Scale};
+use serde::{Deserialize, Serialize};
+use serenity::all::{ActionRowComponent, ButtonStyle, ChannelId, ComponentInteraction, CreateActionRow, CreateAttachment, CreateButton, CreateInputText, CreateInteractionResponse, CreateInteractionResponseMessage, CreateMessage, CreateModal, GuildId, Http, InputTextStyle, Member, MessageId, ModalInteraction, RoleId, Timestamp, UserId};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::commands::moderation::welcome::WelcomeFont;
+use crate::handlers::goodbye::kick_without_goodbye;
+use crate::handlers::welcome;
+use crate::handlers::misc::auto_role::assign_auto_roles;
+use crate::handlers::misc::raid::is_raid_mode;
+use crate::log_handle;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+use crate::utils::scheduler::ScheduledEntry;
+use crate::utils::template::format_duration;
+
+/// Tiempo y número de intentos por defecto para resolver el captcha
+const DEFAULT_CAPTCHA_TIMEOUT: i64 = 600;
+const DEFAULT_CAPTCHA_ATTEMPTS: u32 = 3;
+
+/// Caracteres del código, sin los que se confunden entre sí (`0` y `O`, `1` e `I`)
+const CAPTCHA_CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const CAPTCHA_LENGTH: u16 = 6;
+
+/// Tamaño de la imagen del captcha
+const CAPTCHA_WIDTH: u32 = 340;
+const CAPTCHA_HEIGHT: u32 = 110;
+
+/// Prefijos de los `custom_id` del botón y del formulario, seguidos del id del miembro
+pub const CAPTCHA_BUTTON_PREFIX: &str = "captcha:";
+pub const CAPTCHA_MODAL_PREFIX: &str = "captcha_modal:";
+const CAPTCHA_INPUT_ID: &str = "captcha_answer";
+
+/// # Captcha pendiente de un miembro nuevo
+///
+/// - `attempts` son los intentos que le quedan
+/// - `expires` es el momento (Unix) en el que se expulsa al miembro si no lo ha resuelto
+/// - `channel_id` y `message_id` identifican el mensaje del captcha, que se borra al terminar
+/// - `restricted` indica que el miembro entró durante un raid o con una cuenta demasiado nueva,
+///   por lo que al verificarse no recibe la bienvenida ni los roles automáticos
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CaptchaEntry {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub code: String,
+    pub attempts: u32,
+    pub expires: i64,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    #[serde(default)]
+    pub restricted: bool,
+}
+
+impl CaptchaEntry {
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("captcha")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_entry(guild_id: GuildId, user_id: UserId) -> SurrealResult<Option<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM captcha WHERE guild_id = $guild_id AND user_id = $user_id";
+        let entry: Option<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?
+            .take(0)?;
+
+        Ok(entry)
+    }
+
+    pub async fn get_expired(now: i64) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM captcha WHERE expires <= $now";
+        let entries: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("now", now))
+            .await?
+            .take(0)?;
+
+        Ok(entries)
+    }
+
+    pub async fn set_attempts(guild_id: GuildId, user_id: UserId, attempts: u32) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "UPDATE captcha SET attempts = $attempts WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("attempts", attempts))
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_entry(guild_id: GuildId, user_id: UserId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE captcha WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// # Envía un captcha a un miembro nuevo si la verificación está activada
+///
+/// - El miembro recibe un mensaje con la imagen y el botón para responder, y después el rol sin verificar
+/// - El captcha se guarda en la base de datos, por lo que el tiempo límite sobrevive a los reinicios del Bot
+/// - Devuelve `true` si el miembro debe verificarse antes de recibir la bienvenida
+pub async fn start_captcha(ctx: &serenity::Context, member: &Member, restricted: bool) -> Result<bool, Error> {
+    let guild_id = member.guild_id;
+    let user_id = member.user.id;
+    let captcha = GuildData::verify_data(guild_id).await?
+        .map(|data| data.captcha)
+        .unwrap_or_default();
+
+    if captcha.enabled.as_deref() != Some("true") { return Ok(false) }
+
+    let Some(role_id) = captcha.role.and_then(|role| role.parse::<RoleId>().ok()) else { return Ok(false) };
+    let Some(channel_id) = captcha.channel.and_then(|channel| channel.parse::<ChannelId>().ok()) else { return Ok(false) };
+    let timeout = captcha.timeout.and_then(|timeout| timeout.parse::<i64>().ok()).unwrap_or(DEFAULT_CAPTCHA_TIMEOUT);
+    let attempts = captcha.attempts.and_then(|attempts| attempts.parse::<u32>().ok()).unwrap_or(DEFAULT_CAPTCHA_ATTEMPTS);
+
+    let code = generate_code();
+    let file = format!("/tmp/{user_id}_captcha.png");
+    render_captcha(&code, &file)?;
+
+    let button = CreateButton::new(format!("{CAPTCHA_BUTTON_PREFIX}{user_id}"))
+        .label("Resolver captcha")
+        .style(ButtonStyle::Primary);
+
+    let content = format!(
+        "<@{user_id}> Escribe el código de la imagen para acceder al servidor. Tienes {} y {attempts} intentos",
+        format_duration(timeout)
+    );
+
+    let message = CreateMessage::default()
+        .content(content)
+        .add_file(CreateAttachment::path(&file).await?)
+        .components(vec![CreateActionRow::Buttons(vec![button])]);
+
+    let sent = channel_id.send_message(&ctx.http, message).await;
+
+    // Borrar la imágen generada después de usarla, aunque no se haya podido enviar
+    remove_file(file)?;
+    let sent = sent?;
+
+    let entry = CaptchaEntry {
+        guild_id,
+        user_id,
+        code,
+        attempts,
+        expires: Timestamp::now().unix_timestamp() + timeout,
+        channel_id,
+        message_id: sent.id,
+        restricted,
+    };
+
+    // Un captcha anterior puede seguir guardado si el miembro salió con el Bot apagado
+    CaptchaEntry::remove_entry(guild_id, user_id).await?;
+    entry.save_to_db().await?;
+
+    // El rol se asigna al final para no dejar al miembro sin verificar y sin captcha si algo falla
+    if let Err(why) = member.add_role(&ctx.http, role_id).await {
+        finish_captcha(&ctx.http, &entry).await?;
+        return Err(why.into())
+    }
+
+    Ok(true)
+}
+
+/// # Genera un código aleatorio para el captcha
+fn generate_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..CAPTCHA_LENGTH)
+        .map(|_| char::from(CAPTCHA_CHARSET[rng.gen_range(0..CAPTCHA_CHARSET.len())]))
+        .collect()
+}
+
+/// # Color aleatorio con cada canal dentro de `range`
+fn random_color(rng: &mut impl Rng, range: Range<u8>) -> Rgba<u8> {
+    Rgba([rng.gen_range(range.clone()), rng.gen_range(range.clone()), rng.gen_range(range), 255])
+}
+
+/// # Dibuja la imagen del captcha
+///
+/// - Cada carácter usa una fuente PT Serif (normal o cursiva), tamaño, posición y color al azar
+/// - Se añaden puntos y líneas de ruido para dificultar la lectura automática
+fn render_captcha(code: &str, output_path: &str) -> UnwrapResult<()> {
+    let fonts = [WelcomeFont::Regular, WelcomeFont::Italic]
+        .into_iter()
+        .map(|font| Font::try_from_vec(std::fs::read(font.path())?).into_result())
+        .collect::<UnwrapResult<Vec<_>>>()?;
+
+    let mut rng = rand::thread_rng();
+    let mut canvas = RgbaImage::from_pixel(CAPTCHA_WIDTH, CAPTCHA_HEIGHT, Rgba([235, 235, 225, 255]));
+    let width = CAPTCHA_WIDTH as i32;
+    let height = CAPTCHA_HEIGHT as i32;
+
+    for _ in 0..250 {
+        let center = (rng.gen_range(0..width), rng.gen_range(0..height));
+        let color = random_color(&mut rng, 120..220);
+        draw_filled_circle_mut(&mut canvas, center, rng.gen_range(1..3), color);
+    }
+
+    for (index, character) in code.chars().enumerate() {
+        let font = &fonts[rng.gen_range(0..fonts.len())];
+        let scale = Scale::uniform(rng.gen_range(58.0..74.0));
+        let x = 20 + index as i32 * 50 + rng.gen_range(-5..6);
+        let y = rng.gen_range(5..30);
+        let color = random_color(&mut rng, 0..110);
+        draw_text_mut(&mut canvas, color, x, y, scale, font, &character.to_string());
+    }
+
+    for _ in 0..6 {
+        let start = (rng.gen_range(0.0..width as f32), rng.gen_range(0.0..height as f32));
+        let end = (rng.gen_range(0.0..width as f32), rng.gen_range(0.0..height as f32));
+        let color = random_color(&mut rng, 0..160);
+        draw_line_segment_mut(&mut canvas, start, end, color);
+    }
+
+    canvas.save(output_path)?;
+
+    Ok(())
+}
+
+/// # Esta función maneja el botón del captcha
+///
+/// - Solo el miembro al que se envió el captcha puede responderlo
+/// - Se abre un formulario para escribir el código de la imagen
+pub async fn handle_button(ctx: &serenity::Context, mc: &ComponentInteraction, user_id: &str) -> Result<(), Error> {
+    if mc.user.id.to_string() != user_id {
+        let response = CreateInteractionResponseMessage::new()
+            .content("Este captcha no es para ti")
+            .ephemeral(true);
+
+        mc.create_response(&ctx.http, CreateInteractionResponse::Message(response)).await?;
+        return Ok(())
+    }
+
+    let input = CreateInputText::new(InputTextStyle::Short, "Código de la imagen", CAPTCHA_INPUT_ID)
+        .min_length(CAPTCHA_LENGTH)
+        .max_length(CAPTCHA_LENGTH);
+
+    let modal = CreateModal::new(format!("{CAPTCHA_MODAL_PREFIX}{user_id}"), "Verificación")
+        .components(vec![CreateActionRow::InputText(input)]);
+
+    mc.create_response(&ctx.http, CreateInteractionResponse::Modal(modal)).await?;
+
+    Ok(())
+}
+
+/// # Esta función maneja la respuesta al formulario del captcha
+///
+/// - El código no distingue mayúsculas de minúsculas
+/// - Una respuesta correcta da acceso al servidor
+/// - Cada respuesta incorrecta resta un intento; sin intentos, se expulsa al miembro
+pub async fn handle_answer(ctx: &serenity::Context, modal: &ModalInteraction) -> Result<(), Error> {
+    let Some(user_id) = modal.data.custom_id.strip_prefix(CAPTCHA_MODAL_PREFIX) else { return Ok(()) };
+    if modal.user.id.to_string() != user_id { return Ok(()) }
+
+    let guild_id = modal.guild_id.into_result()?;
+    let Some(entry) = CaptchaEntry::get_entry(guild_id, modal.user.id).await? else {
+        respond(ctx, modal, "El captcha ha caducado").await?;
+        return Ok(())
+    };
+
+    let answer = modal.data.components
+        .iter()
+        .flat_map(|row| &row.components)
+        .find_map(|component| match component {
+            ActionRowComponent::InputText(input) if input.custom_id == CAPTCHA_INPUT_ID => input.value.clone(),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    if answer.trim().eq_ignore_ascii_case(&entry.code) {
+        respond(ctx, modal, "Verificación completada. ¡Bienvenido!").await?;
+        return verify_member(ctx, &entry).await
+    }
+
+    let attempts = entry.attempts.saturating_sub(1);
+    if attempts == 0 {
+        respond(ctx, modal, "Has agotado los intentos del captcha").await?;
+        return kick_unverified(&ctx.http, &entry, "Captcha fallido").await
+    }
+
+    CaptchaEntry::set_attempts(guild_id, modal.user.id, attempts).await?;
+    respond(ctx, modal, &format!("Código incorrecto. Te quedan {attempts} intentos")).await?;
+
+    Ok(())
+}
+
+/// # Responde al formulario con un mensaje que solo ve el miembro
+async fn respond(ctx: &serenity::Context, modal: &ModalInteraction, content: &str) -> Result<(), Error> {
+    let response = CreateInteractionResponseMessage::new()
+        .content(content)
+        .ephemeral(true);
+
+    modal.create_response(&ctx.http, CreateInteractionResponse::Message(response)).await?;
+
+    Ok(())
+}
+
+/// # Da acceso al servidor a un miembro que ha resuelto el captcha
+///
+/// - Se cambia el rol sin verificar por el rol verificado, si está configurado
+/// - Después recibe los roles automáticos y la tarjeta de bienvenida, igual que si no hubiera captcha
+/// - Los miembros restringidos al entrar, o que se verifican durante un raid, solo cambian de rol
+async fn verify_member(ctx: &serenity::Context, entry: &CaptchaEntry) -> Result<(), Error> {
+    finish_captcha(&ctx.http, entry).await?;
+
+    let captcha = GuildData::verify_data(entry.guild_id).await?
+        .map(|data| data.captcha)
+        .unwrap_or_default();
+
+    let member = entry.guild_id.member(&ctx.http, entry.user_id).await?;
+    if let Some(role_id) = captcha.role.and_then(|role| role.parse::<RoleId>().ok()) {
+        member.remove_role(&ctx.http, role_id).await?;
+    }
+
+    if let Some(role_id) = captcha.verified_role.and_then(|role| role.parse::<RoleId>().ok()) {
+        member.add_role(&ctx.http, role_id).await?;
+    }
+
+    if entry.restricted || is_raid_mode(entry.guild_id) { return Ok(()) }
+
+    assign_auto_roles(&ctx.http, &member).await?;
+
+    welcome::handler(ctx, &member).await
+}
+
+/// # Expulsa a un miembro que no ha resuelto el captcha
+async fn kick_unverified(http: &Http, entry: &CaptchaEntry, reason: &str) -> Result<(), Error> {
+    finish_captcha(http, entry).await?;
+    kick_without_goodbye(http, entry.guild_id, entry.user_id, reason).await?;
+
+    Ok(())
+}
+
+/// # Borra el captcha de la base de datos y su mensaje
+///
+/// - Si el mensaje ya no existe, solo se registra el error
+async fn finish_captcha(http: &Http, entry: &CaptchaEntry) -> Result<(), Error> {
+    CaptchaEntry::remove_entry(entry.guild_id, entry.user_id).await?;
+    if let Err(why) = entry.channel_id.delete_message(http, entry.message_id).await {
+        log_handle!("No se pudo borrar el captcha de {}: {why}", entry.user_id);
+    }
+
+    Ok(())
+}
+
+/// # Cancela el captcha de un miembro que ha abandonado el servidor
+pub async fn cancel_captcha(http: &Http, guild_id: GuildId, user_id: UserId) -> Result<(), Error> {
+    let Some(entry) = CaptchaEntry::get_entry(guild_id, user_id).await? else { return Ok(()) };
+
+    finish_captcha(http, &entry).await
+}
+
+impl ScheduledEntry for CaptchaEntry {
+    const FETCH_ERROR: &'static str = "No se pudieron obtener los captchas caducados";
+
+    async fn get_due(now: i64) -> SurrealResult<Vec<Self>> {
+        Self::get_expired(now).await
+    }
+
+    /// Expulsa a los miembros que no resolvieron el captcha a tiempo
+    async fn run(&self, http: &Http) -> Result<(), Error> {
+        kick_unverified(http, self, "Captcha no resuelto a tiempo").await
+    }
+
+    fn error_message(&self) -> String {
+        format!("No se pudo expulsar a {} por no resolver el captcha", self.user_id)
+    }
+}
@@ -0,0 +1,44 @@
+use poise::ChoiceParameter;
+use serenity::all::{ChannelId, CreateEmbed, CreateMessage, GuildId, Http};
+
+use crate::commands::moderation::setters::set_dry_run::{DryRunModule, ALL_MODULES};
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+/// # Comprueba si un módulo está en modo de prueba en el servidor
+pub async fn is_dry_run(guild_id: GuildId, module: DryRunModule) -> UnwrapResult<bool> {
+    let modules = GuildData::verify_data(guild_id).await?
+        .and_then(|data| data.dry_run.modules)
+        .unwrap_or_default();
+
+    Ok(modules == ALL_MODULES || modules.split(',').any(|name| name == module.as_str()))
+}
+
+/// # Intercepta una acción de moderación automática
+///
+/// - Si el módulo está en modo de prueba, registra en el canal de Logs la acción que se habría tomado
+/// - Devuelve `true` si la acción fue interceptada y no debe ejecutarse
+pub async fn intercept(
+    http: &Http,
+    guild_id: GuildId,
+    module: DryRunModule,
+    action: &str,
+) -> UnwrapResult<bool> {
+    if !is_dry_run(guild_id, module).await? { return Ok(false) }
+
+    let log_channel = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let embed = CreateEmbed::default()
+        .title(format!("🧪 Modo de prueba: {}", module.name()))
+        .description(format!("Se habría realizado la siguiente acción:\n> {action}"))
+        .color(0x00ff_a500);
+
+    log_channel.send_message(http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(true)
+}
@@ -1,7 +1,12 @@
-use serenity::all::{Member, Message, Timestamp};
+use serenity::all::{Member, Message};
 use poise::serenity_prelude as serenity;
 use crate::utils::CommandResult;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
 use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::punishment::silence_member;
+use crate::utils::template::format_duration;
 
 /// Silencia al autor del mensaje y elimina el mensaje
 pub async fn handle_everyone(
@@ -13,10 +18,16 @@ pub async fn handle_everyone(
 ) -> CommandResult {
 
     if check_admin_exception(admin_role_id, member, ctx) { return Ok(()) }
-    let time = Timestamp::now().unix_timestamp() + time_out_timer;
-    let time = Timestamp::from_unix_timestamp(time)?;
+    let action = format!(
+        "Silenciar a <@{}> durante {} y borrar su mensaje en <#{}>",
+        member.user.id,
+        format_duration(time_out_timer),
+        message.channel_id
+    );
 
-    member.disable_communication_until_datetime(&ctx.http, time).await?;
+    if dry_run::intercept(&ctx.http, member.guild_id, DryRunModule::Everyone, &action).await? { return Ok(()) }
+    silence_member(&ctx.http, member, time_out_timer).await?;
+    ghost_ping::ignore_deletion(message.id);
     message.delete(&ctx.http).await?;
 
     Ok(())
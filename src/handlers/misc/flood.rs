@@ -0,0 +1,321 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use poise::ChoiceParameter;
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, EditChannel, GuildId, Message, MessageId, UserId};
+
+use crate::log_handle;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::commands::moderation::setters::set_flood::FloodAction;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::punishment::silence_member;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+const DEFAULT_RATE: usize = 5;
+const DEFAULT_RATE_WINDOW_SECONDS: u64 = 5;
+const DEFAULT_SIMILARITY: f64 = 0.85;
+const DEFAULT_SIMILAR_COUNT: usize = 4;
+const DEFAULT_SIMILAR_WINDOW_SECONDS: u64 = 30;
+
+/// Segundos entre mensajes del modo lento
+const SLOWMODE_SECONDS: u16 = 10;
+
+/// Tiempo que dura el modo lento antes de restaurar el valor anterior del canal
+const SLOWMODE_DURATION: Duration = Duration::from_secs(300);
+
+/// Ventana más larga que se puede configurar con `set_flood`
+const MAX_WINDOW: Duration = Duration::from_secs(600);
+
+/// # Mensaje reciente de un usuario
+#[derive(Debug, Clone)]
+struct RecentMessage {
+    sent_at: Instant,
+    message_id: MessageId,
+    channel_id: ChannelId,
+    normalized: String,
+}
+
+/// # Historial de mensajes recientes
+///
+/// - Cada servidor y usuario tiene su propio historial
+/// - Se utiliza `DashMap` para no bloquear a todos los servidores a la vez
+static FLOOD_TRACKER: LazyLock<DashMap<(GuildId, UserId), VecDeque<RecentMessage>>> = LazyLock::new(DashMap::new);
+
+/// # Limpia el historial de flood
+///
+/// - Se borran los mensajes más antiguos que la ventana más larga que se puede configurar
+/// - Los usuarios sin mensajes recientes se eliminan del historial
+pub fn cleanup_flood_tracker(now: Instant) {
+    FLOOD_TRACKER.retain(|_, history| {
+        history.retain(|message| now.duration_since(message.sent_at) < MAX_WINDOW);
+        !history.is_empty()
+    });
+}
+
+/// # Límites de flood configurados en el servidor
+struct FloodLimits {
+    enabled: bool,
+    action: FloodAction,
+    rate: usize,
+    rate_window: Duration,
+    similarity: f64,
+    similar_count: usize,
+    similar_window: Duration,
+}
+
+impl FloodLimits {
+    async fn from_guild(guild_id: GuildId) -> UnwrapResult<Self> {
+        let flood = GuildData::verify_data(guild_id).await?
+            .map(|data| data.flood)
+            .unwrap_or_default();
+
+        Ok(Self {
+            enabled: flood.enabled.as_deref() == Some("true"),
+            action: flood.action.as_deref().map_or(FloodAction::Delete, FloodAction::from),
+            rate: flood.rate
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_RATE),
+            rate_window: Duration::from_secs(flood.rate_window
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_RATE_WINDOW_SECONDS)),
+            similarity: flood.similarity
+                .and_then(|value| value.parse::<f64>().ok())
+                .map_or(DEFAULT_SIMILARITY, |percent| percent / 100.0),
+            similar_count: flood.similar_count
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_SIMILAR_COUNT),
+            similar_window: Duration::from_secs(flood.similar_window
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_SIMILAR_WINDOW_SECONDS)),
+        })
+    }
+}
+
+/// # Normaliza un mensaje antes de compararlo
+///
+/// - Se pasa a minúsculas y se eliminan los signos de puntuación y los espacios
+/// - Las letras repetidas seguidas se reducen a una (`holaaa` -> `hola`)
+pub fn normalize_message(content: &str) -> String {
+    let mut normalized = String::with_capacity(content.len());
+    for character in content.to_lowercase().chars().filter(|character| character.is_alphanumeric()) {
+        if !normalized.ends_with(character) {
+            normalized.push(character);
+        }
+    }
+
+    normalized
+}
+
+/// # Calcula el parecido entre dos textos
+///
+/// - Se utiliza la distancia de edición (Levenshtein) normalizada por la longitud del texto más largo
+/// - Devuelve un valor entre `0.0` (distintos) y `1.0` (iguales)
+pub fn similarity(first: &str, second: &str) -> f64 {
+    let first = first.chars().collect::<Vec<_>>();
+    let second = second.chars().collect::<Vec<_>>();
+    let longest = first.len().max(second.len());
+    if longest == 0 { return 1.0 }
+
+    let mut previous = (0..=second.len()).collect::<Vec<_>>();
+    let mut current = vec![0; second.len() + 1];
+    for (i, first_character) in first.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, second_character) in second.iter().enumerate() {
+            let substitution = previous[j] + usize::from(first_character != second_character);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    1.0 - previous[second.len()] as f64 / longest as f64
+}
+
+/// # Motivo por el que se detectó un flood
+enum FloodReason {
+    Rate(usize),
+    Similar(usize),
+}
+
+impl FloodReason {
+    fn describe(&self) -> String {
+        match self {
+            Self::Rate(count) => format!("{count} mensajes en poco tiempo"),
+            Self::Similar(count) => format!("{count} mensajes parecidos"),
+        }
+    }
+}
+
+/// # Registra el mensaje y revisa si el usuario está haciendo flood
+///
+/// - Devuelve el motivo y los mensajes implicados si se supera algún límite
+fn record_message(message: &Message, guild_id: GuildId, limits: &FloodLimits) -> Option<(FloodReason, Vec<RecentMessage>)> {
+    let now = Instant::now();
+    let longest_window = limits.rate_window.max(limits.similar_window);
+    let recent = RecentMessage {
+        sent_at: now,
+        message_id: message.id,
+        channel_id: message.channel_id,
+        normalized: normalize_message(&message.content),
+    };
+
+    let mut history = FLOOD_TRACKER.entry((guild_id, message.author.id)).or_default();
+    history.retain(|message| now.duration_since(message.sent_at) < longest_window);
+    history.push_back(recent.clone());
+
+    let in_rate_window = history
+        .iter()
+        .filter(|message| now.duration_since(message.sent_at) < limits.rate_window)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if in_rate_window.len() > limits.rate {
+        history.clear();
+        return Some((FloodReason::Rate(in_rate_window.len()), in_rate_window))
+    }
+
+    if recent.normalized.is_empty() { return None }
+    let similar = history
+        .iter()
+        .filter(|message| now.duration_since(message.sent_at) < limits.similar_window)
+        .filter(|message| similarity(&message.normalized, &recent.normalized) >= limits.similarity)
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if similar.len() >= limits.similar_count {
+        history.retain(|message| !similar.iter().any(|similar| similar.message_id == message.message_id));
+        return Some((FloodReason::Similar(similar.len()), similar))
+    }
+
+    None
+}
+
+/// # Esta función detecta el flood de mensajes
+///
+/// - Detecta demasiados mensajes dentro de la ventana de tiempo
+/// - Detecta mensajes parecidos aunque no sean idénticos (`hola`, `holaa`, `hola!!`)
+/// - Aplica la acción configurada en el servidor: borrar, silenciar o modo lento
+/// - Devuelve `true` si el mensaje fue sancionado
+pub async fn check_flood(
+    ctx: &serenity::Context,
+    message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+) -> Result<bool, Error> {
+    let limits = FloodLimits::from_guild(guild_id).await?;
+    if !limits.enabled { return Ok(false) }
+
+    let Some((reason, messages)) = record_message(message, guild_id, &limits) else { return Ok(false) };
+    let mut member = guild_id.member(&ctx.http, message.author.id).await?;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) { return Ok(false) }
+
+    let author_id = message.author.id;
+    let action = format!(
+        "{} a <@{author_id}> en <#{}> por enviar {}",
+        limits.action.name(),
+        message.channel_id,
+        reason.describe()
+    );
+
+    // En modo de prueba el mensaje no se sanciona, por lo que debe pasar por el resto de revisiones
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::Flood, &action).await? { return Ok(false) }
+
+    delete_flood_messages(ctx, &messages).await?;
+    match limits.action {
+        FloodAction::Delete => {}
+        FloodAction::Timeout => {
+            let time_out_timer = GuildData::verify_data(guild_id).await?
+                .into_result()?
+                .time_out
+                .time
+                .into_result()?
+                .parse::<i64>()?;
+
+            silence_member(&ctx.http, &mut member, time_out_timer).await?;
+        }
+        FloodAction::Slowmode => enable_slowmode(ctx, message.channel_id).await?,
+    }
+
+    log_flood(ctx, guild_id, message, &limits.action, &reason).await?;
+
+    Ok(true)
+}
+
+/// # Borra los mensajes del flood agrupándolos por canal
+async fn delete_flood_messages(ctx: &serenity::Context, messages: &[RecentMessage]) -> UnwrapResult<()> {
+    let mut by_channel: HashMap<ChannelId, Vec<MessageId>> = HashMap::new();
+    for message in messages {
+        ghost_ping::ignore_deletion(message.message_id);
+        by_channel.entry(message.channel_id).or_default().push(message.message_id);
+    }
+
+    for (channel_id, message_ids) in by_channel {
+        channel_id.delete_messages(&ctx.http, message_ids).await?;
+    }
+
+    Ok(())
+}
+
+/// # Activa el modo lento en un canal
+///
+/// - Se restaura el valor anterior del canal pasados unos minutos
+pub async fn enable_slowmode(ctx: &serenity::Context, channel_id: ChannelId) -> UnwrapResult<()> {
+    let previous = channel_id.to_channel(&ctx.http).await?
+        .guild()
+        .and_then(|channel| channel.rate_limit_per_user)
+        .unwrap_or_default();
+
+    if previous >= SLOWMODE_SECONDS { return Ok(()) }
+    channel_id.edit(&ctx.http, EditChannel::new().rate_limit_per_user(SLOWMODE_SECONDS)).await?;
+
+    let http = ctx.http.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(SLOWMODE_DURATION).await;
+        if let Err(why) = channel_id.edit(&http, EditChannel::new().rate_limit_per_user(previous)).await {
+            log_handle!("No se pudo restaurar el modo lento del canal {channel_id}: {why}");
+        }
+    });
+
+    Ok(())
+}
+
+/// # Esta función envía un `Embed` al canal de Logs con el flood detectado
+async fn log_flood(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    message: &Message,
+    action: &FloodAction,
+    reason: &FloodReason,
+) -> UnwrapResult<()> {
+    let log_channel = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let author = &message.author;
+    let embed = CreateEmbed::default()
+        .title("⚠️ Flood detectado")
+        .author(CreateEmbedAuthor::new(author.name.clone())
+            .icon_url(author.face()))
+        .description(format!(
+            "El usuario <@{}> ha enviado {}.\n**Canal:** <#{}>\n**Acción:** {}\n**Último mensaje:** {}",
+            author.id,
+            reason.describe(),
+            message.channel_id,
+            action.name(),
+            message.content
+        ))
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
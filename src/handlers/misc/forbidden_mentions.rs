@@ -5,13 +5,25 @@ use poise::serenity_prelude as serenity;
 use serenity::all::{GuildId, Message, UserId};
 
 use crate::{DB, log_handle};
+use crate::commands::moderation::setters::set_availability::AvailabilityWindow;
 use crate::commands::moderation::setters::set_forbidden_exception::ForbiddenException;
+use crate::commands::moderation::setters::set_reply_ping_policy::ReplyPingPolicy;
 use crate::utils::{CommandResult, MessageData, Warns};
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
 use crate::handlers::misc::exceptions::check_admin_exception;
-use crate::handlers::misc::warns::handle_warn_system;
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::warns::{handle_warn_system, intercept_warn, warns_counter, MAX_WARNS};
 use crate::utils::config::GuildData;
 use crate::utils::debug::IntoUnwrapResult;
 use crate::utils::embeds::send_warn_embed;
+use crate::utils::template::{format_duration, upgrade_legacy, TemplateContext};
+
+/// Mensaje de advertencia por defecto si el servidor no ha establecido uno
+pub const DEFAULT_WARN_MESSAGE: &str = "{user} Por favor no hagas @ a este usuario. Si estás respondiendo un mensaje, considera responder al mensaje sin usar @";
+
+/// Mensaje de silencio por defecto si el servidor no ha establecido uno
+pub const DEFAULT_TIME_OUT_MESSAGE: &str = "{mention} Has sido silenciado por mencionar a un usuario cuyo rol está prohibido de mencionar";
 
 pub async fn handle_forbidden_user(
     ctx: &serenity::Context,
@@ -33,6 +45,11 @@ pub async fn handle_forbidden_user(
         }
     }
 
+    if AvailabilityWindow::is_available(forbidden_user_id, guild_id).await? {
+        println!("El usuario se encuentra en una ventana de disponibilidad : {}", Location::caller());
+        return Ok(())
+    }
+
     if !new_message.mentions_user_id(forbidden_user_id) {
         println!("No se ha mencionado al usuario prohibido : {}", Location::caller());
         return Ok(())
@@ -46,24 +63,28 @@ pub async fn handle_forbidden_user(
         .into_result()?
         .parse::<i64>()?;
     
-    let warn_message = GuildData::verify_data(guild_id).await?
+    let warn_template = GuildData::verify_data(guild_id).await?
         .into_result()?
         .messages
         .warn
-        .unwrap_or_else(|| {
+        .map_or_else(|| {
             log_handle!("No se ha establecido un mensaje de advertencia: `sent_message.rs` {}", Location::caller());
-            "Por favor no hagas @ a este usuario. Si estás respondiendo un mensaje, considera responder al mensaje sin usar @".to_string()
-        });
-    
-    let warn_message = format!("{} {warn_message}", member.distinct());
-    let time_out_message = GuildData::verify_data(guild_id).await?
+            DEFAULT_WARN_MESSAGE.to_string()
+        }, |warn| upgrade_legacy(&warn, "{user} "));
+
+    let time_out_template = GuildData::verify_data(guild_id).await?
         .into_result()?
         .messages
         .time_out
-        .unwrap_or_else(|| {
+        .map_or_else(|| {
             log_handle!("No se ha establecido un mensaje de silencio: {}", Location::caller());
-            "Has sido silenciado por mencionar a un usuario cuyo rol está prohibido de mencionar".to_string()
-        });
+            DEFAULT_TIME_OUT_MESSAGE.to_string()
+        }, |time_out| upgrade_legacy(&time_out, "{mention} "));
+
+    let template_context = TemplateContext::for_member(ctx, &member)
+        .channel_id(new_message.channel_id)
+        .duration(format_duration(time_out_timer))
+        .max_warns(MAX_WARNS);
     
     let admin_role_id = GuildData::verify_data(guild_id).await?
         .into_result()?
@@ -89,21 +110,49 @@ pub async fn handle_forbidden_user(
         return Ok(())
     }
 
-    let mut warns = Warns::new(author_user_id);
-    let existing_warns = warns.get_warns().await?;
-    warns_counter(&mut warns, existing_warns).await?;
+    if is_reply_ping(new_message, forbidden_user_id) {
+        let reply_ping_policy = GuildData::verify_data(guild_id).await?
+            .into_result()?
+            .forbidden
+            .reply_ping
+            .map_or(ReplyPingPolicy::Escalate, |policy| ReplyPingPolicy::from(policy.as_str()));
+
+        match reply_ping_policy {
+            ReplyPingPolicy::Allow => return Ok(()),
+            ReplyPingPolicy::Warn => {
+                let current_warns = Warns::new(author_user_id).get_warns().await?.map_or(0, |warns| warns.warns);
+                let warn_message = template_context.warns(current_warns).render(&warn_template);
+                new_message.reply(&ctx.http, warn_message).await?;
+                return Ok(())
+            }
+            ReplyPingPolicy::Escalate => {}
+        }
+    }
+
     let channel_id = new_message.channel_id;
-    let warnings = warns.warns;
-    send_warn_embed(ctx, warnings, "./assets/sugerencia.png", channel_id, &warn_message).await?;
-    let message_map = HashMap::new();
     let http = ctx.http.clone();
-    if warns.warns >= 3 {
-        handle_warn_system(&mut member, new_message, message_map, &http, warns, time_out_timer, time_out_message).await?;
+    if !intercept_warn(&http, guild_id, author_user_id, channel_id, time_out_timer).await? {
+        let mut warns = Warns::new(author_user_id);
+        let existing_warns = warns.get_warns().await?;
+        warns_counter(&mut warns, existing_warns).await?;
+        let warnings = warns.warns;
+        let template_context = template_context.warns(warnings);
+        let warn_message = template_context.render(&warn_template);
+        let time_out_message = template_context.render(&time_out_template);
+        send_warn_embed(ctx, guild_id, warnings, channel_id, &warn_message, &template_context).await?;
+        let message_map = HashMap::new();
+        if warns.warns >= MAX_WARNS {
+            handle_warn_system(&mut member, new_message.channel_id, message_map, &http, warns, time_out_timer, time_out_message).await?;
+        }
     }
     
     DB.query("DEFINE INDEX message_id ON TABLE messages COLUMNS message_id UNIQUE").await?;
     let _created: Vec<MessageData> = DB.create("messages").content(data).await?;
-    http.delete_message(new_message.channel_id, new_message.id, None).await?;
+    let action = format!("Borrar el mensaje de <@{author_user_id}> en <#{channel_id}> por mencionar al usuario prohibido");
+    if !dry_run::intercept(&http, guild_id, DryRunModule::Deletions, &action).await? {
+        ghost_ping::ignore_deletion(new_message.id);
+        http.delete_message(new_message.channel_id, new_message.id, None).await?;
+    }
 
     Ok(())
 }
@@ -127,24 +176,28 @@ pub async fn handle_forbidden_role(
         .into_result()?
         .parse::<i64>()?;
 
-    let warn_message = GuildData::verify_data(guild_id).await?
+    let warn_template = GuildData::verify_data(guild_id).await?
         .into_result()?
         .messages
         .warn
-        .unwrap_or_else(|| {
+        .map_or_else(|| {
             log_handle!("No se ha establecido un mensaje de advertencia: `sent_message.rs` {}", Location::caller());
-            format!("{} Por favor no hagas @ a este usuario. Si estás respondiendo un mensaje, considera responder al mensaje sin usar @", member.distinct())
-        });
+            DEFAULT_WARN_MESSAGE.to_string()
+        }, |warn| upgrade_legacy(&warn, "{user} "));
 
-    let warn_message = format!("{} {warn_message}", member.distinct());
-    let time_out_message = GuildData::verify_data(guild_id).await?
+    let time_out_template = GuildData::verify_data(guild_id).await?
         .into_result()?
         .messages
         .time_out
-        .unwrap_or_else(|| {
+        .map_or_else(|| {
             log_handle!("No se ha establecido un mensaje de silencio: {}", Location::caller());
-            format!("{} Has sido silenciado por mencionar a un usuario cuyo rol está prohibido de mencionar", member.distinct())
-        });
+            DEFAULT_TIME_OUT_MESSAGE.to_string()
+        }, |time_out| upgrade_legacy(&time_out, "{mention} "));
+
+    let template_context = TemplateContext::for_member(ctx, &member)
+        .channel_id(new_message.channel_id)
+        .duration(format_duration(time_out_timer))
+        .max_warns(MAX_WARNS);
 
     let admin_exception = check_admin_exception(admin_role_id, &member, ctx);
     if admin_exception {
@@ -152,33 +205,46 @@ pub async fn handle_forbidden_role(
         return Ok(())
     }
 
-    let mut warns = Warns::new(author_user_id);
-    let existing_warns = warns.get_warns().await?;
-    warns_counter(&mut warns, existing_warns).await?;
     let channel_id = new_message.channel_id;
-    send_warn_embed(ctx, warns.warns, "./assets/sugerencia.png", channel_id, &warn_message).await?;
-    let message_map = HashMap::new();
     let http = ctx.http.clone();
-    let mut member = guild_id.member(&ctx.http, author_user_id).await?;
-
-    if warns.warns >= 3 {
-        handle_warn_system(&mut member, new_message, message_map, &http, warns, time_out_timer, time_out_message).await?;
+    if !intercept_warn(&http, guild_id, author_user_id, channel_id, time_out_timer).await? {
+        let mut warns = Warns::new(author_user_id);
+        let existing_warns = warns.get_warns().await?;
+        warns_counter(&mut warns, existing_warns).await?;
+        let template_context = template_context.warns(warns.warns);
+        let warn_message = template_context.render(&warn_template);
+        let time_out_message = template_context.render(&time_out_template);
+        send_warn_embed(ctx, guild_id, warns.warns, channel_id, &warn_message, &template_context).await?;
+        let message_map = HashMap::new();
+        let mut member = guild_id.member(&ctx.http, author_user_id).await?;
+
+        if warns.warns >= MAX_WARNS {
+            handle_warn_system(&mut member, new_message.channel_id, message_map, &http, warns, time_out_timer, time_out_message).await?;
+        }
     }
     
-    http.delete_message(new_message.channel_id, new_message.id, None).await?;
+    let action = format!("Borrar el mensaje de <@{author_user_id}> en <#{channel_id}> por mencionar un rol prohibido");
+    if !dry_run::intercept(&http, guild_id, DryRunModule::Deletions, &action).await? {
+        ghost_ping::ignore_deletion(new_message.id);
+        http.delete_message(new_message.channel_id, new_message.id, None).await?;
+    }
 
     Ok(())
 }
 
-async fn warns_counter(warns: &mut Warns, existing_warns: Option<Warns>) -> CommandResult {
-    if let Some(mut existing_warns) = existing_warns {
-        existing_warns.warns += 1;
-        existing_warns.add_warn().await?;
-        *warns = existing_warns;
-    } else {
-        warns.warns += 1;
-        warns.save_to_db().await?;
-    }
-
-    Ok(())
+/// # Verifica si el mensaje es una respuesta con `@` activado al usuario prohibido
+///
+/// - El mensaje responde a un mensaje del usuario prohibido
+/// - El contenido del mensaje no incluye una mención explícita `<@id>`
+///     (Discord añade al autor del mensaje respondido a `mentions` si la mención está activada)
+pub fn is_reply_ping(new_message: &Message, forbidden_user_id: UserId) -> bool {
+    let replies_to_forbidden_user = new_message
+        .referenced_message
+        .as_ref()
+        .is_some_and(|referenced| referenced.author.id == forbidden_user_id);
+
+    let explicit_mention = new_message.content.contains(&format!("<@{forbidden_user_id}>"))
+        || new_message.content.contains(&format!("<@!{forbidden_user_id}>"));
+
+    replies_to_forbidden_user && !explicit_mention
 }
\ No newline at end of file
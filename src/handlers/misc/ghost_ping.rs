@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude as serenity;
+use regex::Regex;
+use serenity::all::{CreateAllowedMentions, CreateEmbed, CreateEmbedAuthor, CreateMessage, GuildId, MessageId, Timestamp, UserId};
+
+use crate::utils::{CommandResult, MessageData};
+use crate::utils::config::GuildData;
+use crate::utils::debug::UnwrapResult;
+
+/// Tiempo por defecto (en segundos) en el que una mención borrada se considera un ghost ping
+const DEFAULT_WINDOW_SECONDS: i64 = 60;
+
+/// Tiempo que se recuerda un mensaje borrado por el Bot
+///
+/// - Los mensajes que no estaban guardados nunca llegan a `handle_deleted`, así que caducan solos
+const MODERATED_TTL: Duration = Duration::from_secs(300);
+
+/// # Mensajes borrados por el Bot
+///
+/// - Los mensajes que borra el Bot como sanción no deben notificarse como ghost ping
+/// - Se utiliza un `Mutex` de la librería estándar porque nunca se mantiene durante un `.await`
+static MODERATED_MESSAGES: LazyLock<Mutex<HashMap<MessageId, Instant>>> = LazyLock::new(|| {
+    Mutex::new(HashMap::new())
+});
+
+/// Marca un mensaje como borrado por el Bot para que no se notifique como ghost ping
+pub fn ignore_deletion(message_id: MessageId) {
+    if let Ok(mut moderated) = MODERATED_MESSAGES.lock() {
+        moderated.retain(|_, marked_at| marked_at.elapsed() < MODERATED_TTL);
+        moderated.insert(message_id, Instant::now());
+    }
+}
+
+fn is_moderated(message_id: MessageId) -> bool {
+    MODERATED_MESSAGES
+        .lock()
+        .is_ok_and(|mut moderated| moderated.remove(&message_id).is_some())
+}
+
+/// # Esta función extrae los usuarios mencionados en un mensaje
+///
+/// - Reconoce las menciones `<@id>` y `<@!id>`
+/// - No se repiten los usuarios mencionados varias veces
+pub fn extract_user_mentions(content: &str) -> Vec<UserId> {
+    let Ok(mention_re) = Regex::new(r"<@!?(\d+)>") else { return Vec::new() };
+    let mut mentions = Vec::new();
+    for capture in mention_re.captures_iter(content) {
+        let Some(user_id) = capture.get(1).and_then(|id| id.as_str().parse::<u64>().ok()) else { continue };
+        let user_id = UserId::new(user_id);
+        if !mentions.contains(&user_id) {
+            mentions.push(user_id);
+        }
+    }
+
+    mentions
+}
+
+/// # Esta función detecta los ghost pings de un mensaje borrado
+///
+/// - Notifica a los usuarios mencionados si el mensaje se borró dentro de la ventana configurada
+pub async fn handle_deleted(ctx: &serenity::Context, guild_id: GuildId, message: &MessageData) -> CommandResult {
+    if is_moderated(message.message_id) { return Ok(()) }
+    let mentions = extract_user_mentions(&message.message_content);
+    notify_ghost_ping(ctx, guild_id, message, mentions, "borrado").await
+}
+
+/// # Esta función detecta los ghost pings de un mensaje editado
+///
+/// - Notifica a los usuarios cuya mención se eliminó al editar el mensaje
+pub async fn handle_edited(ctx: &serenity::Context, guild_id: GuildId, message: &MessageData, new_content: &str) -> CommandResult {
+    let current_mentions = extract_user_mentions(new_content);
+    let removed_mentions = extract_user_mentions(&message.message_content)
+        .into_iter()
+        .filter(|user_id| !current_mentions.contains(user_id))
+        .collect();
+
+    notify_ghost_ping(ctx, guild_id, message, removed_mentions, "editado").await
+}
+
+/// # Esta función envía la notificación de ghost ping al canal del mensaje
+///
+/// - No se notifica al autor del mensaje, a los Bots ni al usuario prohibido de mencionar
+/// - Solo se permite mencionar a los usuarios notificados para evitar menciones a @everyone o roles
+async fn notify_ghost_ping(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    message: &MessageData,
+    mentions: Vec<UserId>,
+    action: &str,
+) -> CommandResult {
+    let Some(window) = ghost_ping_window(guild_id).await? else { return Ok(()) };
+    let message_age = Timestamp::now().unix_timestamp() - message.message_id.created_at().unix_timestamp();
+    if message_age > window { return Ok(()) }
+
+    let forbidden_user = GuildData::verify_data(guild_id).await?
+        .and_then(|data| data.forbidden.user)
+        .and_then(|user| user.parse::<UserId>().ok());
+
+    let mentions = mentions
+        .into_iter()
+        .filter(|user_id| *user_id != message.author_id)
+        .filter(|user_id| Some(*user_id) != forbidden_user)
+        .filter(|user_id| !ctx.cache.user(*user_id).is_some_and(|user| user.bot))
+        .collect::<Vec<_>>();
+
+    if mentions.is_empty() { return Ok(()) }
+
+    let author = guild_id.member(&ctx.http, message.author_id).await?;
+    let mentions_text = mentions
+        .iter()
+        .map(|user_id| format!("<@{user_id}>"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let embed = CreateEmbed::default()
+        .title("👻 Ghost ping detectado")
+        .author(CreateEmbedAuthor::new(author.distinct())
+            .icon_url(author.face()))
+        .description(format!("**Autor del mensaje:** \n> <@{}>\n**Contenido del mensaje:** \n> {}", message.author_id, message.message_content))
+        .color(0x00ff_a500)
+        .timestamp(message.message_id.created_at());
+
+    let builder = CreateMessage::default()
+        .content(format!("{mentions_text} fuiste mencionado en un mensaje que fue {action}"))
+        .embed(embed)
+        .allowed_mentions(CreateAllowedMentions::new().users(mentions));
+
+    message.channel_id.send_message(&ctx.http, builder).await?;
+
+    Ok(())
+}
+
+/// Obtiene la ventana de detección en segundos o `None` si el servidor desactivó la detección
+async fn ghost_ping_window(guild_id: GuildId) -> UnwrapResult<Option<i64>> {
+    let ghost_ping = GuildData::verify_data(guild_id).await?
+        .map(|data| data.ghost_ping)
+        .unwrap_or_default();
+
+    if ghost_ping.enabled.as_deref() == Some("false") {
+        return Ok(None)
+    }
+
+    let window = ghost_ping.window
+        .and_then(|window| window.parse::<i64>().ok())
+        .unwrap_or(DEFAULT_WINDOW_SECONDS);
+
+    Ok(Some(window))
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use image::imageops::FilterType;
+use poise::serenity_prelude as serenity;
+use serenity::all::{Attachment, ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, GuildId, Message, MessageId, UserId};
+
+use crate::log_handle;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::everyone_case::handle_everyone;
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::link_spam_handler::spam_thresholds;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+/// Segundos en los que se rastrean las imágenes de un usuario
+const IMAGE_SPAM_WINDOW_SECONDS: u64 = 30;
+
+/// Bits distintos permitidos entre dos hashes para considerar las imágenes casi idénticas
+const MAX_HASH_DISTANCE: u32 = 10;
+
+/// Tamaño máximo de las imágenes que se analizan (8 MB)
+const MAX_IMAGE_SIZE: u32 = 8 * 1024 * 1024;
+
+/// # Imagen enviada recientemente por un usuario
+#[derive(Debug, Clone, Copy)]
+struct TrackedImage {
+    hash: u64,
+    channel_id: ChannelId,
+    message_id: MessageId,
+    sent_at: Instant,
+}
+
+/// # Rastreador de imágenes
+///
+/// - Cada servidor y usuario tiene su propio historial de hashes
+/// - Se utiliza `DashMap` para no bloquear a todos los servidores a la vez
+static IMAGE_TRACKER: LazyLock<DashMap<(GuildId, UserId), Vec<TrackedImage>>> = LazyLock::new(DashMap::new);
+
+/// # Limpia el rastreador de imágenes
+///
+/// - Se borran las imágenes que han salido de la ventana de tiempo
+/// - Los usuarios sin imágenes recientes se eliminan del rastreador
+pub fn cleanup_image_tracker(now: Instant) {
+    let window = Duration::from_secs(IMAGE_SPAM_WINDOW_SECONDS);
+    IMAGE_TRACKER.retain(|_, history| {
+        history.retain(|image| now.duration_since(image.sent_at) < window);
+        !history.is_empty()
+    });
+}
+
+/// # Calcula el hash perceptual (dHash) de una imagen
+///
+/// - Se reduce la imagen a 9x8 píxeles en escala de grises
+/// - Cada bit indica si un píxel es más brillante que el de su derecha
+/// - Las imágenes parecidas (redimensionadas, recomprimidas) tienen hashes con pocos bits distintos
+pub fn difference_hash(bytes: &[u8]) -> Option<u64> {
+    let image = image::load_from_memory(bytes).ok()?;
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y).0[0];
+            let right = small.get_pixel(x + 1, y).0[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+
+    Some(hash)
+}
+
+/// Comprueba si dos hashes pertenecen a imágenes casi idénticas
+pub const fn is_near_duplicate(first: u64, second: u64) -> bool {
+    (first ^ second).count_ones() <= MAX_HASH_DISTANCE
+}
+
+/// Comprueba si un adjunto es una imagen que se puede analizar
+fn is_hashable_image(attachment: &Attachment) -> bool {
+    let is_image = attachment.content_type
+        .as_deref()
+        .is_some_and(|content_type| content_type.starts_with("image/"));
+
+    is_image && attachment.size <= MAX_IMAGE_SIZE
+}
+
+/// Descarga una imagen adjunta y calcula su hash sin bloquear el hilo de ejecución
+async fn hash_attachment(attachment: &Attachment) -> Option<u64> {
+    let bytes = match attachment.download().await {
+        Ok(bytes) => bytes,
+        Err(why) => {
+            log_handle!("No se pudo descargar la imagen {}: {why}", attachment.url);
+            return None
+        }
+    };
+
+    tokio::task::spawn_blocking(move || difference_hash(&bytes)).await.ok()?
+}
+
+/// # Registra los hashes de un mensaje
+///
+/// - Devuelve las imágenes casi idénticas del usuario si aparecen en suficientes canales distintos
+fn record_hashes(
+    guild_id: GuildId,
+    message: &Message,
+    hashes: &[u64],
+    channels: usize,
+) -> Option<Vec<TrackedImage>> {
+    let now = Instant::now();
+    let window = Duration::from_secs(IMAGE_SPAM_WINDOW_SECONDS);
+    let key = (guild_id, message.author.id);
+    let mut history = IMAGE_TRACKER.entry(key).or_default();
+    history.retain(|image| now.duration_since(image.sent_at) < window);
+
+    for hash in hashes {
+        history.push(TrackedImage {
+            hash: *hash,
+            channel_id: message.channel_id,
+            message_id: message.id,
+            sent_at: now,
+        });
+    }
+
+    for hash in hashes {
+        let duplicates = history
+            .iter()
+            .filter(|image| is_near_duplicate(image.hash, *hash))
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut channel_ids = duplicates.iter().map(|image| image.channel_id).collect::<Vec<_>>();
+        channel_ids.sort_unstable();
+        channel_ids.dedup();
+        if channel_ids.len() >= channels {
+            history.retain(|image| !duplicates.iter().any(|duplicate| duplicate.message_id == image.message_id));
+
+            // Se suelta la entrada antes de borrarla para no bloquear el `DashMap`
+            let is_empty = history.is_empty();
+            drop(history);
+            if is_empty {
+                IMAGE_TRACKER.remove_if(&key, |_, history| history.is_empty());
+            }
+
+            return Some(duplicates)
+        }
+    }
+
+    None
+}
+
+/// # Esta función detecta la misma imagen enviada en varios canales
+///
+/// - Solo se revisa si el servidor ha activado la detección con `set_image_spam`
+/// - Se calcula un hash perceptual de cada imagen adjunta
+/// - El límite de canales distintos es el mismo que el del spam de enlaces
+/// - Se aplica el mismo flujo que al spam de enlaces: silencio, borrado y registro en Logs
+/// - Devuelve `true` si el mensaje fue sancionado
+pub async fn check_image_spam(
+    ctx: &serenity::Context,
+    new_message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+    time: i64,
+) -> Result<bool, Error> {
+    let images = new_message.attachments
+        .iter()
+        .filter(|attachment| is_hashable_image(attachment))
+        .collect::<Vec<_>>();
+
+    if images.is_empty() { return Ok(false) }
+
+    let Some(guild_data) = GuildData::verify_data(guild_id).await? else { return Ok(false) };
+    if guild_data.image_spam.enabled.as_deref() != Some("true") { return Ok(false) }
+
+    // Se comprueba antes de descargar las imágenes para no analizar las de los administradores
+    let mut member = guild_id.member(&ctx.http, new_message.author.id).await?;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) { return Ok(false) }
+
+    let mut hashes = Vec::new();
+    for attachment in images {
+        if let Some(hash) = hash_attachment(attachment).await {
+            hashes.push(hash);
+        }
+    }
+
+    if hashes.is_empty() { return Ok(false) }
+
+    let thresholds = spam_thresholds(guild_id).await?;
+    let Some(duplicates) = record_hashes(guild_id, new_message, &hashes, thresholds.channels) else { return Ok(false) };
+
+    let author_id = new_message.author.id;
+    let action = format!("Silenciar a <@{author_id}> y borrar {} imágenes repetidas en varios canales", duplicates.len());
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::Spam, &action).await? { return Ok(false) }
+
+    handle_everyone(admin_role_id.to_owned(), &mut member, ctx, time, new_message).await?;
+    delete_duplicates(ctx, new_message.id, &duplicates).await?;
+    log_image_spam(ctx, guild_id, new_message, &duplicates).await?;
+
+    Ok(true)
+}
+
+/// # Borra los mensajes con imágenes repetidas agrupándolos por canal
+///
+/// - El mensaje actual ya lo borra `handle_everyone`
+async fn delete_duplicates(ctx: &serenity::Context, current: MessageId, duplicates: &[TrackedImage]) -> UnwrapResult<()> {
+    let mut by_channel: HashMap<ChannelId, Vec<MessageId>> = HashMap::new();
+    for image in duplicates.iter().filter(|image| image.message_id != current) {
+        let message_ids = by_channel.entry(image.channel_id).or_default();
+        if !message_ids.contains(&image.message_id) {
+            ghost_ping::ignore_deletion(image.message_id);
+            message_ids.push(image.message_id);
+        }
+    }
+
+    for (channel_id, message_ids) in by_channel {
+        channel_id.delete_messages(&ctx.http, message_ids).await?;
+    }
+
+    Ok(())
+}
+
+/// # Esta función envía un `Embed` al canal de Logs con el spam de imágenes detectado
+async fn log_image_spam(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    message: &Message,
+    duplicates: &[TrackedImage],
+) -> UnwrapResult<()> {
+    let log_channel = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let mut channel_ids = duplicates.iter().map(|image| image.channel_id).collect::<Vec<_>>();
+    channel_ids.sort_unstable();
+    channel_ids.dedup();
+    let channels = channel_ids.iter().map(|channel_id| format!("<#{channel_id}>")).collect::<Vec<_>>();
+
+    let author = &message.author;
+    let embed = CreateEmbed::default()
+        .title("⚠️ Spam de imágenes detectado")
+        .author(CreateEmbedAuthor::new(author.name.clone())
+            .icon_url(author.face()))
+        .description(format!(
+            "El usuario <@{}> ha enviado la misma imagen en varios canales.\n**Canales:** {}",
+            author.id,
+            channels.join(", ")
+        ))
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
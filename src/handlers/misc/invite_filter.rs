@@ -0,0 +1,93 @@
+use std::sync::LazyLock;
+
+use poise::serenity_prelude as serenity;
+use regex::Regex;
+use serenity::all::{GuildId, Message};
+
+use crate::log_handle;
+use crate::commands::moderation::setters::set_invite_filter::InvitePartner;
+use crate::handlers::error::{is_discord_error, UNKNOWN_INVITE};
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::warns::warn_and_delete;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+
+/// Mensaje de advertencia al enviar una invitación a otro servidor
+pub const DEFAULT_INVITE_WARN_MESSAGE: &str = "{user} No se permiten invitaciones a otros servidores";
+
+/// # Expresión regular de las invitaciones de Discord
+///
+/// - Reconoce `discord.gg/código`, `discord.com/invite/código` y `discordapp.com/invite/código`
+/// - Discord convierte estas invitaciones en enlaces aunque no lleven `https://`
+static INVITE_REGEX: LazyLock<Option<Regex>> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:https?://)?(?:www\.)?(?:discord\.gg|discord(?:app)?\.com/invite)/([a-z0-9-]+)").ok()
+});
+
+/// # Esta función extrae los códigos de invitación de un mensaje
+pub fn extract_invite_codes(text: &str) -> Vec<String> {
+    INVITE_REGEX
+        .as_ref()
+        .map(|invite_re| invite_re
+            .captures_iter(text)
+            .filter_map(|captures| captures.get(1))
+            .map(|code| code.as_str().to_string())
+            .collect())
+        .unwrap_or_default()
+}
+
+/// # Esta función revisa las invitaciones de un mensaje
+///
+/// - Se obtiene el servidor al que apunta cada invitación
+/// - Se permiten las invitaciones a este servidor y a los servidores asociados
+/// - Las invitaciones caducadas o inválidas (`Unknown Invite`) se ignoran porque no llevan a ningún servidor
+/// - Las invitaciones que no se pueden verificar por otro error, o que no llevan a un servidor, no se permiten
+/// - Si alguna invitación no está permitida, se borra el mensaje y se advierte al autor
+/// - Los miembros con cualquiera de los dos roles de administrador están exentos
+/// - Devuelve `true` si el mensaje fue sancionado
+pub async fn check_invites(
+    ctx: &serenity::Context,
+    new_message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+) -> Result<bool, Error> {
+    let codes = extract_invite_codes(&new_message.content);
+    if codes.is_empty() { return Ok(false) }
+
+    let Some(guild_data) = GuildData::verify_data(guild_id).await? else { return Ok(false) };
+    if guild_data.invite_filter.enabled.as_deref() != Some("true") { return Ok(false) }
+
+    // Los dos roles de administrador están exentos
+    let member = guild_id.member(&ctx.http, new_message.author.id).await?;
+    let admin_role_2_id = guild_data.admins.role_2;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) || check_admin_exception(admin_role_2_id, &member, ctx) { return Ok(false) }
+
+    let partners = InvitePartner::get_partners(guild_id).await?;
+    let mut reason = None;
+    for code in &codes {
+        let invite = match ctx.http.get_invite(code, false, false, None).await {
+            Ok(invite) => invite,
+            Err(why) if is_discord_error(&why, UNKNOWN_INVITE) => continue,
+            Err(why) => {
+                log_handle!("No se pudo verificar la invitación {code}, se trata como no permitida: {why}");
+                reason = Some(format!("por enviar la invitación `{code}` que no se pudo verificar"));
+                break
+            }
+        };
+
+        let Some(target) = invite.guild.map(|guild| guild.id) else {
+            reason = Some(format!("por enviar la invitación `{code}` que no lleva a un servidor"));
+            break
+        };
+
+        let is_allowed = target == guild_id || partners.iter().any(|partner| partner.partner_id == target);
+        if !is_allowed {
+            reason = Some(format!("por enviar una invitación al servidor `{target}`"));
+            break
+        }
+    }
+
+    let Some(reason) = reason else { return Ok(false) };
+    warn_and_delete(ctx, new_message, guild_id, DEFAULT_INVITE_WARN_MESSAGE, &reason).await?;
+
+    Ok(true)
+}
@@ -0,0 +1,202 @@
+use poise::serenity_prelude as serenity;
+use reqwest::Url;
+use serenity::all::{GuildId, Message};
+
+use crate::commands::moderation::setters::set_domain::{DomainList, LinkDomain};
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::link_spam_handler::extract_links;
+use crate::handlers::misc::phishing::{matched_phishing_domain, punish_phishing};
+use crate::handlers::misc::warns::warn_and_delete;
+use crate::utils::Error;
+
+/// Mensaje de advertencia al enviar un enlace bloqueado
+pub const DEFAULT_LINK_WARN_MESSAGE: &str = "{user} Ese enlace no está permitido en este servidor";
+
+/// # Dominios que suelen suplantarse
+///
+/// - Se comparan junto a los dominios permitidos del servidor para detectar imitaciones
+const PROTECTED_DOMAINS: [&str; 8] = [
+    "discord.com",
+    "discord.gg",
+    "discord.gift",
+    "discordapp.com",
+    "discord.media",
+    "steamcommunity.com",
+    "steampowered.com",
+    "youtube.com",
+];
+
+/// # Caracteres que se confunden visualmente con letras latinas
+///
+/// - Se incluyen letras cirílicas y griegas, dígitos y letras parecidas entre sí
+const CONFUSABLES: [(char, char); 28] = [
+    ('а', 'a'), ('е', 'e'), ('о', 'o'), ('р', 'p'), ('с', 'c'), ('х', 'x'), ('у', 'y'),
+    ('і', 'i'), ('ј', 'j'), ('ѕ', 's'), ('ԁ', 'd'), ('ӏ', 'i'), ('ɡ', 'g'), ('ո', 'n'),
+    ('α', 'a'), ('ο', 'o'), ('ν', 'v'), ('ρ', 'p'), ('τ', 't'), ('κ', 'k'), ('ι', 'i'),
+    ('0', 'o'), ('1', 'i'), ('3', 'e'), ('5', 's'), ('l', 'i'), ('|', 'i'), ('!', 'i'),
+];
+
+/// # Resultado de revisar un enlace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkVerdict {
+    /// El dominio está en la lista de permitidos
+    Allowed,
+    /// El dominio no está en ninguna lista
+    Neutral,
+    /// El dominio está bloqueado, contiene el motivo
+    Blocked(String),
+}
+
+/// # Obtiene el dominio de un enlace en minúsculas y punycode
+///
+/// - `Url` convierte los dominios internacionalizados (IDN) a punycode
+pub fn extract_domain(link: &str) -> Option<String> {
+    let url = Url::parse(link).ok()?;
+    let host = url.host_str()?.trim_end_matches('.').to_lowercase();
+    if host.is_empty() { return None }
+
+    Some(host)
+}
+
+/// # Compara un dominio con un patrón de la lista
+///
+/// - `*.ejemplo.com` coincide solo con los subdominios
+/// - `ejemplo.com` coincide con el dominio y sus subdominios
+/// - Cualquier otro `*` funciona como comodín
+pub fn domain_matches(host: &str, pattern: &str) -> bool {
+    if let Some(base) = pattern.strip_prefix("*.") {
+        if !base.contains('*') {
+            return host.ends_with(&format!(".{base}"))
+        }
+    }
+
+    if pattern.contains('*') {
+        return wildcard_matches(pattern, host)
+    }
+
+    host == pattern || host.ends_with(&format!(".{pattern}"))
+}
+
+/// Compara un texto con un patrón donde `*` coincide con cualquier cantidad de caracteres
+fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    let parts = pattern.split('*').collect::<Vec<_>>();
+    let (Some(first), Some(last)) = (parts.first(), parts.last()) else { return false };
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false
+    }
+
+    let mut remaining = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        let Some(index) = remaining.find(part) else { return false };
+        remaining = &remaining[index + part.len()..];
+    }
+
+    true
+}
+
+/// # Convierte un dominio a su "esqueleto" visual
+///
+/// - Se decodifica el punycode y se reemplazan los caracteres confundibles
+/// - `rn` se lee como `m` y `vv` como `w`
+/// - Dos dominios con el mismo esqueleto se ven iguales a simple vista
+fn skeleton(domain: &str) -> String {
+    let (unicode, _) = idna::domain_to_unicode(domain);
+    let skeleton = unicode
+        .to_lowercase()
+        .chars()
+        .map(|character| CONFUSABLES
+            .iter()
+            .find(|(confusable, _)| *confusable == character)
+            .map_or(character, |(_, latin)| *latin))
+        .collect::<String>();
+
+    skeleton.replace("rn", "m").replace("vv", "w")
+}
+
+/// # Detecta si un dominio imita a otro
+///
+/// - Se comparan las últimas etiquetas del dominio con el dominio protegido
+/// - Devuelve el dominio imitado si los esqueletos coinciden pero los dominios no
+pub fn impersonated_domain<'a>(host: &str, protected: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let host_labels = host.split('.').collect::<Vec<_>>();
+    protected.into_iter().find(|protected| {
+        let label_count = protected.split('.').count();
+        if host_labels.len() < label_count { return false }
+
+        let suffix = host_labels[host_labels.len() - label_count..].join(".");
+        suffix != *protected && skeleton(&suffix) == skeleton(protected)
+    })
+}
+
+/// # Revisa un dominio con las listas del servidor
+///
+/// - La lista de permitidos tiene prioridad sobre la de bloqueados
+/// - Los dominios que imitan a uno permitido o protegido se bloquean
+pub fn check_domain(host: &str, domains: &[LinkDomain]) -> LinkVerdict {
+    let matches_list = |list: DomainList| domains
+        .iter()
+        .filter(|domain| domain.list() == list)
+        .find(|domain| domain_matches(host, &domain.domain));
+
+    if matches_list(DomainList::Allow).is_some() {
+        return LinkVerdict::Allowed
+    }
+
+    if let Some(blocked) = matches_list(DomainList::Block) {
+        return LinkVerdict::Blocked(format!("el dominio `{host}` coincide con `{}` de la lista de bloqueados", blocked.domain))
+    }
+
+    let allowed = domains
+        .iter()
+        .filter(|domain| domain.list() == DomainList::Allow && !domain.domain.contains('*'))
+        .map(|domain| domain.domain.as_str());
+
+    if let Some(impersonated) = impersonated_domain(host, PROTECTED_DOMAINS.into_iter().chain(allowed)) {
+        return LinkVerdict::Blocked(format!("el dominio `{host}` imita a `{impersonated}`"))
+    }
+
+    LinkVerdict::Neutral
+}
+
+/// # Esta función revisa todos los enlaces de un mensaje
+///
+/// - Si algún enlace es de phishing, se silencia al autor y se borra el mensaje
+/// - Si algún enlace está bloqueado, se borra el mensaje y se advierte al autor
+/// - Los administradores están exentos de las listas del servidor
+/// - Devuelve `true` si el mensaje fue sancionado
+pub async fn check_links(
+    ctx: &serenity::Context,
+    new_message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+) -> Result<bool, Error> {
+    let links = extract_links(&new_message.content);
+    if links.is_empty() { return Ok(false) }
+
+    let hosts = links
+        .iter()
+        .filter_map(|link| extract_domain(link))
+        .collect::<Vec<_>>();
+
+    // Los enlaces de phishing se sancionan sin pasar por las listas del servidor
+    if let Some(domain) = hosts.iter().find_map(|host| matched_phishing_domain(host)) {
+        punish_phishing(ctx, new_message, guild_id, &domain).await?;
+        return Ok(true)
+    }
+
+    let domains = LinkDomain::get_domains(guild_id).await?;
+    let blocked = hosts
+        .iter()
+        .find_map(|host| match check_domain(host, &domains) {
+            LinkVerdict::Blocked(reason) => Some(reason),
+            LinkVerdict::Allowed | LinkVerdict::Neutral => None,
+        });
+
+    let Some(reason) = blocked else { return Ok(false) };
+    let member = guild_id.member(&ctx.http, new_message.author.id).await?;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) { return Ok(false) }
+
+    warn_and_delete(ctx, new_message, guild_id, DEFAULT_LINK_WARN_MESSAGE, &format!("porque {reason}")).await?;
+
+    Ok(true)
+}
@@ -5,92 +5,43 @@ use poise::serenity_prelude as serenity;
 use regex::Regex;
 use serenity::all::{ChannelId, CreateEmbedAuthor, CreateMessage, GetMessages, GuildId, Message, UserId};
 use serenity::builder::CreateEmbed;
-use tokio::sync::Mutex;
 use tokio::time::{Duration, sleep};
 
 use crate::utils::CommandResult;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
 use crate::handlers::misc::everyone_case::handle_everyone;
+use crate::handlers::misc::flood::cleanup_flood_tracker;
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::image_spam::cleanup_image_tracker;
+use crate::handlers::misc::mass_mention::cleanup_mention_tracker;
+use crate::handlers::misc::spam_tracker::{SpamThresholds, SpamTracker, SpamVerdict, DEFAULT_SPAM_CHANNELS, DEFAULT_SPAM_WINDOW_SECONDS};
 use crate::utils::config::GuildData;
-use crate::utils::debug::IntoUnwrapResult;
-
-/// # Estructura de rastreador de mensajes
-/// 
-/// - Almacena el ID del autor del mensaje
-/// - Almacena el contenido del mensaje
-/// - Almacena los IDs de los canales
-/// - Almacena el tiempo del último mensaje
-#[derive(Debug)]
-struct MessageTracker {
-    author_id: UserId,
-    message_content: Arc<String>,
-    channel_ids: Vec<ChannelId>,
-    last_message_time: Instant,
-}
-
-/// Implementación de la estructura de rastreador de mensajes
-/// 
-/// - Implementa un método para crear un rastreador de mensajes por defecto
-/// - Implementa un método para modificar el ID del autor del mensaje
-/// - Implementa un método para modificar el contenido del mensaje
-/// - Implementa un método para modificar los IDs de los canales
-/// - Se sigue el patrón de diseño Builder
-impl MessageTracker {
-    /// # Default implementado manualmente
-    /// 
-    /// - Es necesario implementar `Default` manualmente
-    ///     ya que no puede establecer un valor por defecto para `Instant::now()`
-    pub fn default() -> Self {
-        Self {
-            author_id: UserId::default(),
-            message_content: Arc::new(String::default()),
-            channel_ids: Vec::new(),
-            last_message_time: Instant::now(),
-        }
-    }
-
-    /// # Modifica el ID del autor del mensaje
-    /// 
-    /// - Modifica el ID del autor del mensaje
-    ///     El patrón de diseño Builder consiste en mutar `self` y devolver `Self`
-    ///     con el campo modificado por el valor proporcionado como argumento 
-    /// - Devuelve la estructura de rastreador de mensajes
-    const fn author_id(mut self, author_id: UserId) -> Self {
-        self.author_id = author_id;
-        self
-    }
-
-    /// # Modifica el contenido del mensaje
-    ///
-    /// El patrón de diseño Builder consiste en mutar `self` y devolver `Self`
-    ///     con el campo modificado por el valor proporcionado como argumento 
-    /// - Devuelve la estructura de rastreador de mensajes
-    fn message_content(mut self, message_content: Arc<String>) -> Self {
-        self.message_content = message_content;
-        self
-    }
-
-    /// # Modifica los IDs de los canales
-    ///
-    /// El patrón de diseño Builder consiste en mutar `self` y devolver `Self`
-    ///     con el campo modificado por el valor proporcionado como argumento
-    /// - Devuelve la estructura de rastreador de mensajes
-    fn channel_ids(mut self, channel_ids: Vec<ChannelId>) -> Self {
-        self.channel_ids = channel_ids;
-        self
-    }
-}
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
 
 /// # Rastreador de mensajes
 /// 
-/// - Almacena un vector de rastreadores de mensajes
-/// - Se utiliza un `LazyLock` para evitar problemas de concurrencia
-/// - Se utiliza un Mutex para evitar problemas de concurrencia
-/// - Se utiliza un vector para almacenar los rastreadores de mensajes
+/// - Se utiliza un `LazyLock` para inicializar el rastreador una sola vez
 /// - Se utiliza static para almacenar el rastreador de 
 ///     mensajes de forma global y evitar la pérdida de datos
-static MESSAGE_TRACKER: LazyLock<Mutex<Vec<MessageTracker>>> = LazyLock::new(|| {
-    Mutex::new(Vec::new())
-});
+/// - El rastreador se divide por servidor y usuario, por lo que no necesita un `Mutex`
+static SPAM_TRACKER: LazyLock<SpamTracker> = LazyLock::new(SpamTracker::new);
+
+/// # Obtiene los límites de spam configurados en el servidor
+pub async fn spam_thresholds(guild_id: GuildId) -> UnwrapResult<SpamThresholds> {
+    let spam = GuildData::verify_data(guild_id).await?
+        .map(|data| data.spam)
+        .unwrap_or_default();
+
+    Ok(SpamThresholds {
+        channels: spam.channels
+            .and_then(|value| value.parse::<usize>().ok())
+            .map_or(DEFAULT_SPAM_CHANNELS, |channels| channels.max(2)),
+        window: Duration::from_secs(spam.window
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_SPAM_WINDOW_SECONDS)),
+    })
+}
 
 /// # Esta función extrae un enlace de un mensaje
 ///
@@ -99,12 +50,39 @@ pub fn extract_link(text: &str) -> Option<String> {
     Regex::new(r"(https?://\S+)").map_or(None, |url_re| url_re.find(text).map(|m| m.as_str().to_string()))
 }
 
+/// # Esta función extrae todos los enlaces de un mensaje
+///
+/// - Devuelve un vector vacío si el mensaje no contiene enlaces
+pub fn extract_links(text: &str) -> Vec<String> {
+    Regex::new(r"(https?://\S+)").map_or_else(|_| Vec::new(), |url_re| url_re
+        .find_iter(text)
+        .map(|m| m.as_str().to_string())
+        .collect())
+}
+
+/// # Registra un mensaje con enlaces en el rastreador de spam
+///
+/// - Devuelve los canales donde se repitió el mensaje si se considera spam
+/// - Lo usan tanto el módulo de spam como la regla de moderación automática `LinkSpam`
+pub async fn record_link_spam(
+    message_content: &Arc<String>,
+    channel_id: ChannelId,
+    author_id: UserId,
+    guild_id: GuildId
+) -> UnwrapResult<Option<Vec<ChannelId>>> {
+    let thresholds = spam_thresholds(guild_id).await?;
+    let verdict = SPAM_TRACKER.record(guild_id, author_id, message_content, channel_id, thresholds, Instant::now());
+    let SpamVerdict::Spam(channel_ids) = verdict else { return Ok(None) };
+
+    Ok(Some(channel_ids))
+}
+
 /// # Esta función comprueba si un mensaje es spam
 ///
-/// - Si el mensaje se repite en el mismo canal, borra el vector
-/// - Si el mensaje no existe, crea un nuevo rastreador de mensajes y añádelo a la lista
-/// - Si el siguiente mensaje no coincide con el mensaje anterior, borra el vector
-/// - Si se detecta spam, se envía un mensaje al canal de registro, se borran los mensajes de spam y se limpia el vector
+/// - Cada mensaje se registra en el rastreador del servidor y autor
+/// - Si el mensaje se repite en el mismo canal, se deja de rastrear al autor
+/// - Si el mensaje se repite en suficientes canales distintos dentro de la ventana de tiempo,
+///     se silencia al autor, se borran los mensajes de spam y se envía un mensaje al canal de registro
 pub async fn spam_checker(
     message_content: &Arc<String>,
     channel_id: ChannelId,
@@ -115,57 +93,14 @@ pub async fn spam_checker(
     guild_id: GuildId
 ) -> CommandResult {
     let author_id = new_message.author.id;
-    let mut member = guild_id.member(&ctx.http, new_message.author.id).await?;
-    let mut message_tracker = MESSAGE_TRACKER.lock().await;
-
-    if let Some(last_message) = message_tracker
-        .iter()
-        .last()
-    {
-        if last_message.author_id == author_id && last_message.message_content != *message_content {
-            message_tracker.clear();
-        }
-    }
+    let Some(channel_ids) = record_link_spam(message_content, channel_id, author_id, guild_id).await? else { return Ok(()) };
 
-    let message = if let Some(message) = message_tracker
-        .iter_mut()
-        .find(|m| m.author_id == author_id && m.message_content == *message_content)
-    {
-        // Inicializa el tiempo del último mensaje
-        message.last_message_time = Instant::now();
+    let action = format!("Silenciar a <@{author_id}> y borrar sus mensajes repetidos en {} canales", channel_ids.len());
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::Spam, &action).await? { return Ok(()) }
 
-        // Si el mensaje existe y el canal no está en la lista de canales, añade el canal a la lista de canales
-        if message.channel_ids.contains(&channel_id) {
-            // Si el mensaje se repite en el mismo canal, borra el vector
-            // Debug: println!("Message repeated in the same channel, clearing the vector");
-            message_tracker.clear();
-
-            return Ok(());
-        }
-        message.channel_ids.push(channel_id);
-
-        message
-    } else {
-        // Si el mensaje no existe, crea un nuevo rastreador de mensajes y añádelo a la lista
-        let message = MessageTracker::default()
-            .author_id(author_id)
-            .message_content(message_content.clone())
-            .channel_ids(vec![channel_id]);
-
-        message_tracker.push(message);
-        message_tracker.last_mut().into_result()?
-    };
-
-    if message.channel_ids.len() >= 3 {
-        handle_everyone(admin_role_id.to_owned(), &mut member, ctx, time, new_message).await?;
-        delete_spam_messages(message, ctx, author_id, message_content.clone(), guild_id).await?;
-
-        // Limpia completamente el rastreador de mensajes para reiniciar el rastreo de mensajes
-        message_tracker.retain(|m| m.author_id != author_id);
-    }
-    // Debug: println!("Tracker: {message_tracker:#?}");
-
-    drop(message_tracker);
+    let mut member = guild_id.member(&ctx.http, author_id).await?;
+    handle_everyone(admin_role_id.to_owned(), &mut member, ctx, time, new_message).await?;
+    delete_spam_messages(&channel_ids, ctx, author_id, message_content.clone(), guild_id).await?;
 
     Ok(())
 }
@@ -174,15 +109,15 @@ pub async fn spam_checker(
 /// 
 /// - Borra cada mensaje individualmente en donde 
 ///     el autor del mensaje y el contenido del mensaje coinciden
-async fn delete_spam_messages(
-    message: &MessageTracker,
+pub async fn delete_spam_messages(
+    channel_ids: &[ChannelId],
     ctx: &serenity::Context,
     author_id: UserId,
     message_content: Arc<String>,
     guild_id: GuildId
 ) -> CommandResult {
     // Borra cada mensaje individualmente
-    for channel_id in &message.channel_ids {
+    for channel_id in channel_ids {
         let channel = channel_id.to_channel(ctx).await?;
         let serenity::Channel::Guild(channel) = channel else {
             return Ok(())
@@ -191,6 +126,7 @@ async fn delete_spam_messages(
         let messages = channel.messages(&ctx.http, GetMessages::new()).await?;
         for message in messages {
             if message.author.id == author_id && message.content == *message_content {
+                ghost_ping::ignore_deletion(message.id);
                 message.delete(&ctx.http).await?;
             }
         }
@@ -239,19 +175,20 @@ async fn create_embed(
     Ok(())
 }
 
-/// # Esta función limpia el rastreador de mensajes
+/// # Esta función limpia los rastreadores de mensajes
 /// 
-/// - Limpia el rastreador de mensajes si el tiempo del último mensaje es mayor a 5 segundos
-/// - Se utiliza un bucle para limpiar el rastreador de mensajes cada segundo
+/// - Elimina los mensajes rastreados cuya ventana de tiempo ha caducado
+/// - También limpia el historial de flood, de menciones y de imágenes, para no guardar a los usuarios que dejan de escribir
+/// - Se utiliza un bucle para limpiar los rastreadores de mensajes cada segundo
 pub fn message_tracker_cleaner() {
     tokio::spawn(async {
         loop {
             sleep(Duration::from_secs(1)).await;
-
-            let mut message_tracker = MESSAGE_TRACKER.lock().await;
-            if !message_tracker.is_empty() {
-                message_tracker.retain(|m| m.last_message_time.elapsed() < Duration::from_secs(5));
-            }
+            let now = Instant::now();
+            SPAM_TRACKER.cleanup(now);
+            cleanup_flood_tracker(now);
+            cleanup_mention_tracker(now);
+            cleanup_image_tracker(now);
         }
     });
 }
\ No newline at end of file
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, GuildId, Message, UserId};
+
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::everyone_case::handle_everyone;
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+const DEFAULT_MAX_PER_MESSAGE: usize = 10;
+const DEFAULT_MAX_PER_WINDOW: usize = 20;
+const DEFAULT_WINDOW_SECONDS: u64 = 60;
+/// Ventana más larga que se puede configurar con `set_mass_mention`
+const MAX_WINDOW: Duration = Duration::from_secs(3600);
+
+/// # Rastreador de menciones
+///
+/// - Almacena, por servidor y usuario, el momento y la cantidad de menciones de cada mensaje
+/// - Se utiliza para detectar menciones masivas repartidas en varios mensajes
+static MENTION_TRACKER: LazyLock<DashMap<(GuildId, UserId), Vec<(Instant, usize)>>> = LazyLock::new(DashMap::new);
+
+/// # Limpia el rastreador de menciones
+///
+/// - Se borran las menciones más antiguas que la ventana más larga que se puede configurar
+/// - Los usuarios sin menciones recientes se eliminan del rastreador
+pub fn cleanup_mention_tracker(now: Instant) {
+    MENTION_TRACKER.retain(|_, history| {
+        history.retain(|(sent_at, _)| now.duration_since(*sent_at) < MAX_WINDOW);
+        !history.is_empty()
+    });
+}
+
+/// # Límites de menciones configurados en el servidor
+struct MentionLimits {
+    enabled: bool,
+    max_per_message: usize,
+    max_per_window: usize,
+    window: Duration,
+}
+
+impl MentionLimits {
+    async fn from_guild(guild_id: GuildId) -> UnwrapResult<Self> {
+        let mass_mention = GuildData::verify_data(guild_id).await?
+            .map(|data| data.mass_mention)
+            .unwrap_or_default();
+
+        Ok(Self {
+            enabled: mass_mention.enabled.as_deref() == Some("true"),
+            max_per_message: mass_mention.per_message
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_MAX_PER_MESSAGE),
+            max_per_window: mass_mention.per_window
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_MAX_PER_WINDOW),
+            window: Duration::from_secs(mass_mention.window
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_WINDOW_SECONDS)),
+        })
+    }
+}
+
+/// # Esta función cuenta las menciones únicas de un mensaje
+///
+/// - Cuenta los usuarios mencionados sin contar al autor del mensaje
+/// - Cuenta los roles mencionados
+pub fn count_unique_mentions(message: &Message) -> usize {
+    let users = message.mentions
+        .iter()
+        .map(|user| user.id)
+        .filter(|user_id| *user_id != message.author.id)
+        .collect::<HashSet<_>>();
+
+    let roles = message.mention_roles
+        .iter()
+        .collect::<HashSet<_>>();
+
+    users.len() + roles.len()
+}
+
+/// # Esta función detecta menciones masivas
+///
+/// - Solo se revisa si el servidor lo ha activado con `set_mass_mention`
+/// - Si el mensaje supera el límite de menciones por mensaje, se sanciona al autor
+/// - Si la suma de menciones dentro de la ventana de tiempo supera el límite, se sanciona al autor
+/// - La sanción reutiliza el flujo de `handle_everyone` (aislamiento y borrado del mensaje)
+/// - Devuelve `true` si el mensaje fue sancionado; en modo de prueba solo se registra la acción
+pub async fn check_mass_mention(
+    ctx: &serenity::Context,
+    message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+    time: i64,
+) -> UnwrapResult<bool> {
+    let mentions = count_unique_mentions(message);
+    if mentions == 0 { return Ok(false) }
+
+    let limits = MentionLimits::from_guild(guild_id).await?;
+    if !limits.enabled { return Ok(false) }
+
+    let key = (guild_id, message.author.id);
+    let mentions_in_window = {
+        let mut history = MENTION_TRACKER.entry(key).or_default();
+        history.retain(|(sent_at, _)| sent_at.elapsed() < limits.window);
+        history.push((Instant::now(), mentions));
+        history.iter().map(|(_, count)| count).sum::<usize>()
+    };
+
+    let exceeded = mentions > limits.max_per_message || mentions_in_window > limits.max_per_window;
+    if !exceeded { return Ok(false) }
+
+    MENTION_TRACKER.remove(&key);
+
+    let mut member = guild_id.member(&ctx.http, message.author.id).await?;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) { return Ok(false) }
+
+    let action = format!(
+        "Silenciar a <@{}> por mencionar a {mentions} usuarios o roles ({mentions_in_window} recientes) y borrar su mensaje en <#{}>",
+        message.author.id,
+        message.channel_id
+    );
+
+    // En modo de prueba el mensaje no se sanciona, así que se sigue revisando como cualquier otro
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::Everyone, &action).await? { return Ok(false) }
+
+    handle_everyone(admin_role_id.clone(), &mut member, ctx, time, message).await?;
+    log_mass_mention(ctx, guild_id, message, mentions, mentions_in_window).await?;
+
+    Ok(true)
+}
+
+/// # Esta función envía un `Embed` al canal de Logs con la mención masiva detectada
+async fn log_mass_mention(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    message: &Message,
+    mentions: usize,
+    mentions_in_window: usize,
+) -> UnwrapResult<()> {
+    let log_channel = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let author = &message.author;
+    let embed = CreateEmbed::default()
+        .title("⚠️ Mención masiva detectada")
+        .author(CreateEmbedAuthor::new(author.name.clone())
+            .icon_url(author.face()))
+        .description(format!(
+            "El usuario <@{}> ha sido silenciado por mencionar a demasiados usuarios o roles.\n**Canal:** <#{}>\n**Menciones en el mensaje:** {mentions}\n**Menciones recientes:** {mentions_in_window}\n**Mensaje:** {}",
+            author.id,
+            message.channel_id,
+            message.content
+        ))
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
@@ -5,4 +5,22 @@ pub mod everyone_case;
 pub mod attachment_case;
 pub mod link_spam_handler;
 pub mod reaction_add;
-pub mod buttons;
\ No newline at end of file
+pub mod buttons;
+pub mod ghost_ping;
+pub mod mass_mention;
+pub mod dry_run;
+pub mod spam_tracker;
+pub mod link_filter;
+pub mod phishing;
+pub mod flood;
+pub mod invite_filter;
+pub mod image_spam;
+pub mod raid;
+pub mod account_age;
+pub mod word_filter;
+pub mod text_filter;
+pub mod punishment;
+pub mod automod;
+pub mod native_automod;
+pub mod auto_role;
+pub mod captcha;
\ No newline at end of file
@@ -0,0 +1,307 @@
+use std::panic::Location;
+use std::sync::{Arc, LazyLock};
+
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use poise::serenity_prelude as serenity;
+use serenity::all::{Action, ActionExecution, AutomodEventType, ChannelId, EditAutoModRule, GuildId, Http, RoleId, RuleId, Trigger, UserId};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Duration};
+
+use crate::commands::moderation::filter::{FilterAction, FilterKind, FilterRule};
+use crate::commands::moderation::setters::set_availability::{is_available_at, next_change, AvailabilityWindow};
+use crate::commands::moderation::setters::set_forbidden_exception::ForbiddenException;
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::forbidden_mentions::DEFAULT_WARN_MESSAGE;
+use crate::handlers::misc::punishment::silence_member;
+use crate::handlers::misc::warns::warn_member;
+use crate::handlers::misc::word_filter::{strictest_match, DEFAULT_FILTER_WARN_MESSAGE};
+use crate::log_handle;
+use crate::utils::Error;
+use crate::utils::config::{GuildData, NativeAutomod};
+use crate::utils::debug::IntoUnwrapResult;
+use crate::utils::template::upgrade_legacy;
+
+/// Nombre de la regla de AutoMod que bloquea las menciones prohibidas
+const MENTION_RULE_NAME: &str = "Menciones prohibidas (Bot)";
+
+/// Nombre de la regla de AutoMod que bloquea las palabras del filtro
+const WORD_RULE_NAME: &str = "Filtro de palabras (Bot)";
+
+/// Mensaje que muestra Discord al bloquear una mención prohibida
+const MENTION_BLOCK_MESSAGE: &str = "No puedes mencionar a este usuario o rol. Si estás respondiendo, responde sin usar @";
+
+/// Mensaje que muestra Discord al bloquear una palabra del filtro
+const WORD_BLOCK_MESSAGE: &str = "Tu mensaje contiene una palabra prohibida";
+
+/// Límites de Discord para las reglas de palabras clave
+const MAX_KEYWORDS: usize = 1000;
+const MAX_KEYWORD_LENGTH: usize = 60;
+const MAX_REGEX_PATTERNS: usize = 10;
+const MAX_REGEX_LENGTH: usize = 260;
+
+/// # Resincronizaciones programadas para el próximo cambio de disponibilidad
+///
+/// - Cada servidor tiene como máximo una; al volver a sincronizar se reemplaza
+static AVAILABILITY_TIMERS: LazyLock<DashMap<GuildId, JoinHandle<()>>> = LazyLock::new(DashMap::new);
+
+/// # Palabras clave y expresiones regulares de una regla de AutoMod
+#[derive(Default)]
+struct Keywords {
+    strings: Vec<String>,
+    regex_patterns: Vec<String>,
+}
+
+impl Keywords {
+    const fn is_empty(&self) -> bool {
+        self.strings.is_empty() && self.regex_patterns.is_empty()
+    }
+}
+
+/// # Menciones que bloquea AutoMod
+///
+/// - El contenido de una mención es `<@id>`, por lo que se buscan con comodines
+/// - Si el usuario prohibido tiene una excepción activa o está dentro de una ventana de
+///   disponibilidad, no se bloquean sus menciones
+async fn mention_keywords(guild_data: &GuildData, windows: &[AvailabilityWindow]) -> Result<Keywords, Error> {
+    let mut keywords = Keywords::default();
+    let forbidden = &guild_data.forbidden;
+
+    if let Some(user_id) = forbidden.user.as_deref().and_then(|user| user.parse::<UserId>().ok()) {
+        let has_exception = ForbiddenException::have_exception(user_id).await?.unwrap_or(false);
+        if !has_exception && !is_available_at(windows, Utc::now()) {
+            keywords.strings.push(format!("*<@{user_id}>*"));
+            keywords.strings.push(format!("*<@!{user_id}>*"));
+        }
+    }
+
+    if let Some(role) = &forbidden.role {
+        keywords.strings.push(format!("*<@&{role}>*"));
+    }
+
+    Ok(keywords)
+}
+
+/// # Reglas del filtro de palabras que se pueden reflejar en AutoMod
+///
+/// - Las reglas que solo registran no bloquean el mensaje, por lo que se omiten
+/// - Las reglas que superan los límites de Discord se omiten y el Bot las sigue aplicando por su cuenta
+fn word_keywords(rules: &[FilterRule]) -> Keywords {
+    let mut keywords = Keywords::default();
+    for rule in rules.iter().filter(|rule| rule.action != FilterAction::Log) {
+        match rule.kind {
+            FilterKind::Literal if rule.pattern.chars().count() <= MAX_KEYWORD_LENGTH && keywords.strings.len() < MAX_KEYWORDS => {
+                keywords.strings.push(rule.pattern.clone());
+            }
+            FilterKind::Regex if rule.pattern.chars().count() <= MAX_REGEX_LENGTH && keywords.regex_patterns.len() < MAX_REGEX_PATTERNS => {
+                keywords.regex_patterns.push(rule.pattern.clone());
+            }
+            _ => {}
+        }
+    }
+
+    keywords
+}
+
+/// # Construye una regla de palabras clave de AutoMod
+///
+/// - Bloquea el mensaje con `block_message` y, si hay canal de Logs, envía una alerta
+/// - Los administradores están exentos, igual que en los módulos del Bot
+fn keyword_rule(name: &str, keywords: &Keywords, block_message: &str, log_channel: Option<ChannelId>, exempt_roles: &[RoleId]) -> EditAutoModRule<'static> {
+    let mut actions = vec![Action::BlockMessage { custom_message: Some(block_message.to_string()) }];
+    actions.extend(log_channel.map(Action::Alert));
+
+    EditAutoModRule::new()
+        .name(name)
+        .event_type(AutomodEventType::MessageSend)
+        .trigger(Trigger::Keyword {
+            strings: keywords.strings.clone(),
+            regex_patterns: keywords.regex_patterns.clone(),
+            allow_list: Vec::new(),
+        })
+        .actions(actions)
+        .enabled(true)
+        .exempt_roles(exempt_roles.iter().copied())
+}
+
+/// # Crea, actualiza o borra una regla de AutoMod del Bot
+///
+/// - Si la regla guardada ya no existe en Discord, se vuelve a crear
+/// - Devuelve el id de la regla, o `None` si se ha borrado
+async fn upsert_rule(
+    http: &Http,
+    guild_id: GuildId,
+    current: Option<RuleId>,
+    rule: Option<EditAutoModRule<'static>>,
+) -> Result<Option<RuleId>, Error> {
+    let Some(rule) = rule else {
+        if let Some(rule_id) = current {
+            if let Err(why) = guild_id.delete_automod_rule(http, rule_id).await {
+                log_handle!("No se pudo borrar la regla de AutoMod {rule_id}: {why} {}", Location::caller());
+            }
+        }
+
+        return Ok(None)
+    };
+
+    if let Some(rule_id) = current {
+        if let Ok(edited) = guild_id.edit_automod_rule(http, rule_id, rule.clone()).await {
+            return Ok(Some(edited.id))
+        }
+    }
+
+    let created = guild_id.create_automod_rule(http, rule).await?;
+
+    Ok(Some(created.id))
+}
+
+/// # Sincroniza las reglas de AutoMod de Discord con la configuración del Bot
+///
+/// - Una regla bloquea las menciones al usuario y al rol prohibidos antes de que se envíe la notificación
+/// - Otra regla bloquea las palabras y expresiones regulares del filtro de palabras
+/// - Si la sincronización está desactivada, se borran las reglas creadas por el Bot
+/// - Las excepciones dinámicas se revisan en cada sincronización, que se repite al empezar y al terminar
+/// - Las ventanas de disponibilidad se revisan igual, y se programa otra sincronización para su próximo cambio
+pub async fn sync_native_automod(http: &Arc<Http>, guild_id: GuildId) -> Result<(), Error> {
+    let Some(guild_data) = GuildData::verify_data(guild_id).await? else { return Ok(()) };
+    let native_automod = &guild_data.native_automod;
+    let enabled = native_automod.enabled.as_deref() == Some("true");
+    let mention_rule_id = native_automod.mention_rule.as_deref().and_then(|rule| rule.parse::<RuleId>().ok());
+    let word_rule_id = native_automod.word_rule.as_deref().and_then(|rule| rule.parse::<RuleId>().ok());
+
+    let log_channel = guild_data.channels.logs.as_deref().and_then(|channel| channel.parse::<ChannelId>().ok());
+    let exempt_roles = [&guild_data.admins.role, &guild_data.admins.role_2]
+        .into_iter()
+        .filter_map(|role| role.as_deref().and_then(|role| role.parse::<RoleId>().ok()))
+        .collect::<Vec<_>>();
+
+    let forbidden_user = guild_data.forbidden.user.as_deref().and_then(|user| user.parse::<UserId>().ok());
+    let windows = match forbidden_user {
+        Some(user_id) => AvailabilityWindow::get_windows(user_id, guild_id).await?,
+        None => Vec::new(),
+    };
+
+    schedule_availability_resync(http, guild_id, enabled.then(|| next_change(&windows, Utc::now())).flatten());
+    let mentions = mention_keywords(&guild_data, &windows).await?;
+    let words = word_keywords(&FilterRule::get_rules(guild_id).await?);
+
+    let mention_rule = (enabled && !mentions.is_empty())
+        .then(|| keyword_rule(MENTION_RULE_NAME, &mentions, MENTION_BLOCK_MESSAGE, log_channel, &exempt_roles));
+    let word_rule = (enabled && !words.is_empty())
+        .then(|| keyword_rule(WORD_RULE_NAME, &words, WORD_BLOCK_MESSAGE, log_channel, &exempt_roles));
+
+    let mention_rule_id = upsert_rule(http, guild_id, mention_rule_id, mention_rule).await?;
+    let word_rule_id = upsert_rule(http, guild_id, word_rule_id, word_rule).await?;
+
+    let fields = [
+        ("native_automod.mention_rule", mention_rule_id),
+        ("native_automod.word_rule", word_rule_id),
+    ];
+
+    for (field, rule_id) in fields {
+        let value = rule_id.map_or_else(String::new, |rule_id| rule_id.to_string());
+        native_automod.update_field_in_db(field, &value, &guild_id.to_string()).await?;
+    }
+
+    Ok(())
+}
+
+/// # Sincroniza AutoMod después de un cambio en la configuración
+///
+/// - Un error de Discord no debe impedir que se guarde la configuración, por lo que solo se registra
+pub async fn resync_native_automod(http: &Arc<Http>, guild_id: GuildId) {
+    if let Err(why) = sync_native_automod(http, guild_id).await {
+        log_handle!("No se pudo sincronizar AutoMod: {why} {}", Location::caller());
+    }
+}
+
+/// # Programa una sincronización para el próximo cambio de disponibilidad del usuario prohibido
+///
+/// - Reemplaza la sincronización programada anteriormente; con `None` solo la cancela
+fn schedule_availability_resync(http: &Arc<Http>, guild_id: GuildId, change: Option<DateTime<Utc>>) {
+    if let Some((_, handle)) = AVAILABILITY_TIMERS.remove(&guild_id) {
+        handle.abort();
+    }
+
+    let Some(change) = change else { return };
+    let delay = (change - Utc::now()).to_std().unwrap_or_default() + Duration::from_secs(1);
+    let http = http.clone();
+    let handle = tokio::spawn(async move {
+        sleep(delay).await;
+        AVAILABILITY_TIMERS.remove(&guild_id);
+        resync_native_automod(&http, guild_id).await;
+    });
+
+    AVAILABILITY_TIMERS.insert(guild_id, handle);
+}
+
+/// # Sincroniza AutoMod en los servidores que lo tienen activado al iniciar el Bot
+///
+/// - Vuelve a programar las sincronizaciones de las ventanas de disponibilidad, que se pierden al reiniciar
+pub async fn resync_enabled_guilds(http: &Arc<Http>, guild_ids: impl IntoIterator<Item = GuildId>) {
+    for guild_id in guild_ids {
+        let enabled = GuildData::verify_data(guild_id).await
+            .ok()
+            .flatten()
+            .is_some_and(|data| data.native_automod.enabled.as_deref() == Some("true"));
+
+        if enabled {
+            resync_native_automod(http, guild_id).await;
+        }
+    }
+}
+
+/// # Esta función maneja las acciones ejecutadas por AutoMod
+///
+/// - Solo se tienen en cuenta las reglas creadas por el Bot
+/// - Cada regla genera un evento por acción, por lo que solo se cuenta el bloqueo del mensaje
+/// - En las menciones prohibidas, el autor recibe una advertencia como si el Bot hubiera borrado
+///   el mensaje, por lo que puede acabar silenciado al llegar al máximo de advertencias
+/// - En el filtro de palabras se aplica la acción de la regla más severa que coincida:
+///   `Delete` solo bloquea el mensaje, `Warn` suma una advertencia y `Timeout` silencia al autor
+pub async fn handle_execution(ctx: &serenity::Context, execution: &ActionExecution) -> Result<(), Error> {
+    if !matches!(execution.action, Action::BlockMessage { .. }) { return Ok(()) }
+    let Some(channel_id) = execution.channel_id else { return Ok(()) };
+
+    let guild_id = execution.guild_id;
+    let Some(guild_data) = GuildData::verify_data(guild_id).await? else { return Ok(()) };
+    let NativeAutomod { mention_rule, word_rule, .. } = &guild_data.native_automod;
+    let rule_id = execution.rule_id.to_string();
+
+    let action = if mention_rule.as_deref() == Some(rule_id.as_str()) {
+        FilterAction::Warn
+    } else if word_rule.as_deref() == Some(rule_id.as_str()) {
+        let rules = FilterRule::get_rules(guild_id).await?;
+        let matched = strictest_match(&rules, &execution.content)
+            .or_else(|| rules.iter().find(|rule| execution.matched_keyword.as_deref() == Some(rule.pattern.as_str())));
+
+        let Some(rule) = matched else { return Ok(()) };
+        rule.action
+    } else {
+        return Ok(())
+    };
+
+    if action <= FilterAction::Delete { return Ok(()) }
+
+    // Los dos roles de administrador están exentos, igual que en las reglas creadas por el Bot
+    let mut member = guild_id.member(&ctx.http, execution.user_id).await?;
+    let admins = &guild_data.admins;
+    if check_admin_exception(admins.role.clone(), &member, ctx) || check_admin_exception(admins.role_2.clone(), &member, ctx) { return Ok(()) }
+
+    if action == FilterAction::Timeout {
+        let time_out_timer = guild_data.time_out.time.into_result()?.parse::<i64>()?;
+        silence_member(&ctx.http, &mut member, time_out_timer).await?;
+        return Ok(())
+    }
+
+    let warn_template = if mention_rule.as_deref() == Some(rule_id.as_str()) {
+        guild_data.messages.warn
+            .map_or_else(|| DEFAULT_WARN_MESSAGE.to_string(), |warn| upgrade_legacy(&warn, "{user} "))
+    } else {
+        DEFAULT_FILTER_WARN_MESSAGE.to_string()
+    };
+
+    warn_member(ctx, &mut member, channel_id, &warn_template).await?;
+
+    Ok(())
+}
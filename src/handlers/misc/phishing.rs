@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+use std::sync::{LazyLock, RwLock};
+use std::time::SystemTime;
+
+use poise::serenity_prelude as serenity;
+use serde::Deserialize;
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, GuildId, Message};
+use tokio::time::{sleep, Duration};
+
+use crate::log_handle;
+use crate::commands::moderation::setters::set_domain::normalize_domain;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::everyone_case::handle_everyone;
+use crate::utils::{CommandResult, Error};
+use crate::utils::config::GuildData;
+use crate::utils::debug::IntoUnwrapResult;
+
+/// Ruta por defecto del archivo de dominios de phishing
+const DEFAULT_PHISHING_DOMAINS_PATH: &str = "./database/phishing_domains.txt";
+
+/// Segundos entre cada revisión de cambios en el archivo
+const RELOAD_INTERVAL_SECONDS: u64 = 60;
+
+/// # Dominios de phishing cargados desde el archivo
+///
+/// - Se utiliza un `HashSet` para buscar cada dominio en tiempo constante
+/// - Se utiliza un `RwLock` porque las búsquedas son mucho más frecuentes que las recargas
+static PHISHING_DOMAINS: LazyLock<RwLock<HashSet<String>>> = LazyLock::new(|| {
+    RwLock::new(HashSet::new())
+});
+
+/// # Formatos JSON admitidos
+///
+/// - Una lista de dominios: `["dominio.com", ...]`
+/// - Un objeto con la lista en `domains`: `{ "domains": ["dominio.com", ...] }`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PhishingFeed {
+    List(Vec<String>),
+    Object { domains: Vec<String> },
+}
+
+/// Obtiene la ruta del archivo desde la variable de entorno `PHISHING_DOMAINS_PATH`
+fn phishing_domains_path() -> String {
+    dotenvy::var("PHISHING_DOMAINS_PATH").unwrap_or_else(|_| DEFAULT_PHISHING_DOMAINS_PATH.to_string())
+}
+
+/// # Interpreta el contenido del archivo de dominios
+///
+/// - Si el contenido empieza por `[` o `{`, se interpreta como JSON
+/// - Si no, se lee un dominio por línea ignorando las líneas vacías y los comentarios (`#`)
+/// - En las líneas con formato de archivo hosts (`0.0.0.0 dominio.com`) se toma el último valor
+pub fn parse_phishing_domains(content: &str) -> Result<HashSet<String>, serde_json::Error> {
+    let trimmed = content.trim_start();
+    let domains = if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        match serde_json::from_str::<PhishingFeed>(trimmed)? {
+            PhishingFeed::List(domains) | PhishingFeed::Object { domains } => domains,
+        }
+    } else {
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_whitespace().last())
+            .map(str::to_string)
+            .collect()
+    };
+
+    Ok(domains
+        .iter()
+        .filter_map(|domain| normalize_domain(domain))
+        .collect())
+}
+
+/// # Carga el archivo de dominios en memoria
+///
+/// - Devuelve la cantidad de dominios cargados
+async fn load_phishing_domains(path: &str) -> Result<usize, Error> {
+    let content = tokio::fs::read_to_string(path).await?;
+    let domains = parse_phishing_domains(&content)?;
+    let count = domains.len();
+    let mut phishing_domains = PHISHING_DOMAINS.write().map_err(|why| why.to_string())?;
+    *phishing_domains = domains;
+
+    Ok(count)
+}
+
+/// # Busca el dominio de phishing que coincide con un dominio
+///
+/// - Se comprueba el dominio completo y cada dominio padre (`a.b.com`, `b.com`)
+/// - Devuelve el dominio de la lista que coincide
+pub fn matched_phishing_domain(host: &str) -> Option<String> {
+    let phishing_domains = PHISHING_DOMAINS.read().ok()?;
+    let mut candidate = host;
+    loop {
+        if phishing_domains.contains(candidate) {
+            return Some(candidate.to_string())
+        }
+
+        let (_, parent) = candidate.split_once('.')?;
+        if !parent.contains('.') { return None }
+        candidate = parent;
+    }
+}
+
+/// # Esta función recarga el archivo de dominios de phishing cuando cambia
+///
+/// - Se carga el archivo al iniciar el Bot
+/// - Se revisa la fecha de modificación del archivo cada minuto
+/// - Si el archivo no existe o no es válido, se conserva la última lista cargada
+pub fn phishing_list_watcher() {
+    tokio::spawn(async {
+        let path = phishing_domains_path();
+        let mut last_modified: Option<SystemTime> = None;
+        loop {
+            let modified = tokio::fs::metadata(&path)
+                .await
+                .and_then(|metadata| metadata.modified())
+                .ok();
+
+            if modified.is_some() && modified != last_modified {
+                match load_phishing_domains(&path).await {
+                    Ok(count) => {
+                        println!("Se han cargado {count} dominios de phishing desde {path}");
+                        last_modified = modified;
+                    }
+                    Err(why) => log_handle!("No se pudo cargar el archivo de dominios de phishing {path}: {why}"),
+                }
+            }
+
+            sleep(Duration::from_secs(RELOAD_INTERVAL_SECONDS)).await;
+        }
+    });
+}
+
+/// # Esta función sanciona un enlace de phishing
+///
+/// - Se silencia al autor y se borra el mensaje con el flujo de `handle_everyone`
+/// - No se aplica la excepción de administradores: los enlaces de phishing suelen venir de cuentas comprometidas
+/// - Se envía un `Embed` al canal de Logs con el dominio detectado
+pub async fn punish_phishing(
+    ctx: &serenity::Context,
+    new_message: &Message,
+    guild_id: GuildId,
+    domain: &str,
+) -> CommandResult {
+    let author_id = new_message.author.id;
+    let action = format!("Silenciar a <@{author_id}> y borrar su mensaje por enviar un enlace de phishing (`{domain}`)");
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::Phishing, &action).await? { return Ok(()) }
+
+    let guild_data = GuildData::verify_data(guild_id).await?.into_result()?;
+    let time = guild_data.time_out.time
+        .into_result()?
+        .parse::<i64>()?;
+
+    let mut member = guild_id.member(&ctx.http, author_id).await?;
+    handle_everyone(None, &mut member, ctx, time, new_message).await?;
+
+    let log_channel = guild_data.channels.logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let author = &new_message.author;
+    let embed = CreateEmbed::default()
+        .title("🎣 Enlace de phishing detectado")
+        .author(CreateEmbedAuthor::new(author.name.clone())
+            .icon_url(author.face()))
+        .description(format!(
+            "El usuario <@{author_id}> ha sido silenciado por enviar un enlace de phishing.\n**Canal:** <#{}>\n**Dominio:** `{domain}`\n**Mensaje:** {}",
+            new_message.channel_id,
+            new_message.content
+        ))
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
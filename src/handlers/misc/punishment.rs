@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use serenity::all::{EditMember, GuildId, Http, Member, RoleId, Timestamp, UserId};
+use surrealdb::Result as SurrealResult;
+
+use crate::DB;
+use crate::handlers::error::{is_discord_error, UNKNOWN_MEMBER};
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::scheduler::ScheduledEntry;
+
+/// # Miembro en cuarentena
+///
+/// - `roles` son los roles que tenía antes de la cuarentena y que se restauran al terminar
+/// - `until` es el momento (Unix) en el que termina la cuarentena
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct QuarantineEntry {
+    pub guild_id: GuildId,
+    pub user_id: UserId,
+    pub roles: Vec<RoleId>,
+    pub until: i64,
+}
+
+impl QuarantineEntry {
+    pub async fn save_to_db(&self) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let _created: Vec<Self> = DB
+            .create("quarantine")
+            .content(self)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_entry(guild_id: GuildId, user_id: UserId) -> SurrealResult<Option<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM quarantine WHERE guild_id = $guild_id AND user_id = $user_id";
+        let entry: Option<Self> = DB
+            .query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?
+            .take(0)?;
+
+        Ok(entry)
+    }
+
+    pub async fn get_expired(now: i64) -> SurrealResult<Vec<Self>> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "SELECT * FROM quarantine WHERE until <= $now";
+        let entries: Vec<Self> = DB
+            .query(sql_query)
+            .bind(("now", now))
+            .await?
+            .take(0)?;
+
+        Ok(entries)
+    }
+
+    pub async fn set_until(guild_id: GuildId, user_id: UserId, until: i64) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "UPDATE quarantine SET until = $until WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("until", until))
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn remove_entry(guild_id: GuildId, user_id: UserId) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "DELETE quarantine WHERE guild_id = $guild_id AND user_id = $user_id";
+        DB.query(sql_query)
+            .bind(("guild_id", guild_id))
+            .bind(("user_id", user_id))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// # Obtiene el rol de cuarentena si está activado en el servidor
+async fn quarantine_role(guild_id: GuildId) -> Result<Option<RoleId>, Error> {
+    let quarantine = GuildData::verify_data(guild_id).await?
+        .map(|data| data.quarantine)
+        .unwrap_or_default();
+
+    if quarantine.enabled.as_deref() != Some("true") { return Ok(None) }
+
+    Ok(quarantine.role.and_then(|role| role.parse::<RoleId>().ok()))
+}
+
+/// # Silencia a un miembro durante `seconds` segundos
+///
+/// - Si el servidor tiene la cuarentena activada, se le asigna el rol de cuarentena
+/// - En caso contrario, se utiliza el aislamiento de Discord (máximo 28 días)
+pub async fn silence_member(http: &Http, member: &mut Member, seconds: i64) -> Result<(), Error> {
+    let Some(role_id) = quarantine_role(member.guild_id).await? else {
+        let time = Timestamp::from_unix_timestamp(Timestamp::now().unix_timestamp() + seconds)?;
+        member.disable_communication_until_datetime(http, time).await?;
+        return Ok(())
+    };
+
+    quarantine_member(http, member, role_id, seconds).await
+}
+
+/// # Pone en cuarentena a un miembro
+///
+/// - Se guardan sus roles y se reemplazan por el rol de cuarentena
+/// - Los roles gestionados por integraciones (bots, Server Booster) no se pueden quitar y se mantienen
+/// - Si ya estaba en cuarentena, solo se alarga la duración
+pub async fn quarantine_member(http: &Http, member: &mut Member, role_id: RoleId, seconds: i64) -> Result<(), Error> {
+    let guild_id = member.guild_id;
+    let user_id = member.user.id;
+    let until = Timestamp::now().unix_timestamp() + seconds;
+
+    if QuarantineEntry::get_entry(guild_id, user_id).await?.is_some() {
+        QuarantineEntry::set_until(guild_id, user_id, until).await?;
+        return Ok(())
+    }
+
+    let guild_roles = guild_id.roles(http).await?;
+    let (managed, removable): (Vec<RoleId>, Vec<RoleId>) = member.roles
+        .iter()
+        .filter(|role| **role != role_id)
+        .partition(|role| guild_roles.get(*role).is_some_and(|role| role.managed));
+
+    // El registro se guarda antes de quitar los roles para no perderlos si el Bot se reinicia
+    let entry = QuarantineEntry { guild_id, user_id, roles: removable, until };
+    entry.save_to_db().await?;
+
+    let roles = managed.into_iter().chain(std::iter::once(role_id));
+    member.edit(http, EditMember::new().roles(roles)).await?;
+
+    Ok(())
+}
+
+/// # Vuelve a aplicar la cuarentena a un miembro que sale y vuelve a entrar
+///
+/// - Salir del servidor no termina la cuarentena, porque el registro sigue en la base de datos
+/// - Si el servidor desactivó la cuarentena, se aísla al miembro hasta que termine
+/// - Devuelve `true` si el miembro sigue en cuarentena
+pub async fn reapply_quarantine(http: &Http, member: &Member) -> Result<bool, Error> {
+    let Some(entry) = QuarantineEntry::get_entry(member.guild_id, member.user.id).await? else { return Ok(false) };
+    if entry.until <= Timestamp::now().unix_timestamp() { return Ok(false) }
+
+    let Some(role_id) = quarantine_role(member.guild_id).await? else {
+        let time = Timestamp::from_unix_timestamp(entry.until)?;
+        member.guild_id.edit_member(http, member.user.id, EditMember::new().disable_communication_until_datetime(time)).await?;
+        return Ok(true)
+    };
+
+    member.add_role(http, role_id).await?;
+
+    Ok(true)
+}
+
+/// # Termina la cuarentena de un miembro y le devuelve sus roles
+///
+/// - Los roles que se hayan borrado del servidor durante la cuarentena se ignoran
+/// - Si el miembro ya no está en el servidor, solo se borra el registro
+/// - Si no se puede obtener el miembro por otro motivo, se devuelve el error sin borrar el registro
+/// - Devuelve `false` si el miembro no estaba en cuarentena
+pub async fn release_member(http: &Http, guild_id: GuildId, user_id: UserId) -> Result<bool, Error> {
+    let Some(entry) = QuarantineEntry::get_entry(guild_id, user_id).await? else { return Ok(false) };
+
+    // Solo se da por hecho que el miembro salió si Discord lo confirma; ante cualquier otro error
+    // se conserva el registro para que el planificador lo reintente
+    let member = match guild_id.member(http, user_id).await {
+        Ok(member) => Some(member),
+        Err(why) if is_discord_error(&why, UNKNOWN_MEMBER) => None,
+        Err(why) => return Err(why.into()),
+    };
+
+    if let Some(member) = member {
+        let guild_roles = guild_id.roles(http).await?;
+        let managed = member.roles
+            .iter()
+            .filter(|role| guild_roles.get(*role).is_some_and(|role| role.managed))
+            .copied();
+
+        let restored = entry.roles
+            .iter()
+            .filter(|role| guild_roles.contains_key(*role))
+            .copied()
+            .chain(managed)
+            .collect::<Vec<_>>();
+
+        guild_id.edit_member(http, user_id, EditMember::new().roles(restored)).await?;
+    }
+
+    QuarantineEntry::remove_entry(guild_id, user_id).await?;
+
+    Ok(true)
+}
+
+impl ScheduledEntry for QuarantineEntry {
+    const FETCH_ERROR: &'static str = "No se pudieron obtener las cuarentenas caducadas";
+
+    async fn get_due(now: i64) -> SurrealResult<Vec<Self>> {
+        Self::get_expired(now).await
+    }
+
+    async fn run(&self, http: &Http) -> Result<(), Error> {
+        release_member(http, self.guild_id, self.user_id).await?;
+        Ok(())
+    }
+
+    fn error_message(&self) -> String {
+        format!("No se pudo terminar la cuarentena de {}", self.user_id)
+    }
+}
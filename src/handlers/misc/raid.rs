@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use poise::ChoiceParameter;
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, ChannelType, CreateEmbed, CreateMessage, GuildId, Member};
+
+use crate::log_handle;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::commands::moderation::setters::set_raid::RaidAction;
+use crate::handlers::goodbye::kick_without_goodbye;
+use crate::handlers::misc::dry_run;
+use crate::handlers::member_join::JoinCheck;
+use crate::handlers::misc::flood::enable_slowmode;
+use crate::handlers::misc::punishment::silence_member;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+const DEFAULT_RAID_JOINS: usize = 10;
+const DEFAULT_RAID_WINDOW_SECONDS: u64 = 10;
+
+/// Tiempo de silencio si el servidor no tiene uno configurado (1 hora)
+const DEFAULT_RAID_TIME_OUT_SECONDS: i64 = 3600;
+
+/// Tiempo que se mantiene el modo raid desde la última entrada sospechosa
+const RAID_MODE_DURATION: Duration = Duration::from_secs(300);
+
+/// # Entradas recientes de cada servidor
+///
+/// - Se utiliza `DashMap` para no bloquear a todos los servidores a la vez
+static JOIN_TRACKER: LazyLock<DashMap<GuildId, VecDeque<Instant>>> = LazyLock::new(DashMap::new);
+
+/// # Servidores en modo raid y el momento en el que termina
+static RAID_MODE: LazyLock<DashMap<GuildId, Instant>> = LazyLock::new(DashMap::new);
+
+/// # Límites de raid configurados en el servidor
+struct RaidLimits {
+    enabled: bool,
+    joins: usize,
+    window: Duration,
+    action: RaidAction,
+}
+
+impl RaidLimits {
+    async fn from_guild(guild_id: GuildId) -> UnwrapResult<Self> {
+        let raid = GuildData::verify_data(guild_id).await?
+            .map(|data| data.raid)
+            .unwrap_or_default();
+
+        Ok(Self {
+            enabled: raid.enabled.as_deref() == Some("true"),
+            joins: raid.joins
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_RAID_JOINS),
+            window: Duration::from_secs(raid.window
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_RAID_WINDOW_SECONDS)),
+            action: raid.action.as_deref().map_or(RaidAction::None, RaidAction::from),
+        })
+    }
+}
+
+/// # Estado del servidor tras registrar una entrada
+enum RaidState {
+    Calm,
+    Started(usize),
+    Ongoing,
+}
+
+/// # Registra la entrada de un miembro y revisa si el servidor está sufriendo un raid
+///
+/// - Mientras dure el modo raid, cada entrada alarga su duración
+fn record_join(guild_id: GuildId, limits: &RaidLimits) -> RaidState {
+    let now = Instant::now();
+    if let Some(mut until) = RAID_MODE.get_mut(&guild_id) {
+        if now < *until {
+            *until = now + RAID_MODE_DURATION;
+            return RaidState::Ongoing
+        }
+    }
+
+    RAID_MODE.remove_if(&guild_id, |_, until| now >= *until);
+    let mut joins = JOIN_TRACKER.entry(guild_id).or_default();
+    joins.retain(|joined_at| now.duration_since(*joined_at) < limits.window);
+    joins.push_back(now);
+
+    if joins.len() < limits.joins { return RaidState::Calm }
+
+    let count = joins.len();
+    joins.clear();
+    RAID_MODE.insert(guild_id, now + RAID_MODE_DURATION);
+
+    RaidState::Started(count)
+}
+
+/// # Esta función detecta los raids por la cantidad de miembros nuevos
+///
+/// - Al superar el límite se activa el modo raid: modo lento en los canales y aviso a los moderadores
+/// - Durante el modo raid se aplica la acción configurada a cada miembro nuevo
+/// - Durante el modo raid el miembro queda restringido, para omitir la tarjeta de bienvenida,
+///   o expulsado si esa es la acción configurada
+pub async fn check_raid(ctx: &serenity::Context, member: &Member) -> Result<JoinCheck, Error> {
+    let guild_id = member.guild_id;
+    let limits = RaidLimits::from_guild(guild_id).await?;
+    if !limits.enabled { return Ok(JoinCheck::Allowed) }
+
+    match record_join(guild_id, &limits) {
+        RaidState::Calm => return Ok(JoinCheck::Allowed),
+        RaidState::Started(count) => start_raid_mode(ctx, guild_id, count, &limits).await?,
+        RaidState::Ongoing => {}
+    }
+
+    if punish_joiner(ctx, member, &limits).await? { return Ok(JoinCheck::Kicked) }
+
+    Ok(JoinCheck::Restricted)
+}
+
+/// Comprueba si el servidor está en modo raid
+pub fn is_raid_mode(guild_id: GuildId) -> bool {
+    RAID_MODE.get(&guild_id).is_some_and(|until| Instant::now() < *until)
+}
+
+/// # Activa el modo lento en los canales de texto y avisa a los moderadores
+///
+/// - En modo de prueba no se toca ningún canal, pero se sigue avisando a los moderadores
+async fn start_raid_mode(ctx: &serenity::Context, guild_id: GuildId, count: usize, limits: &RaidLimits) -> UnwrapResult<()> {
+    let action = "Activar el modo lento en todos los canales de texto";
+    let slowmode = !dry_run::intercept(&ctx.http, guild_id, DryRunModule::Raid, action).await?;
+    if slowmode {
+        let channels = guild_id.channels(&ctx.http).await?;
+        for channel_id in channels.values().filter(|channel| channel.kind == ChannelType::Text).map(|channel| channel.id) {
+            if let Err(why) = enable_slowmode(ctx, channel_id).await {
+                log_handle!("No se pudo activar el modo lento en el canal {channel_id}: {why:?}");
+            }
+        }
+    }
+
+    alert_staff(ctx, guild_id, count, limits, slowmode).await
+}
+
+/// # Aplica la acción configurada al miembro que entra durante el raid
+///
+/// - Devuelve `true` si el miembro fue expulsado
+async fn punish_joiner(ctx: &serenity::Context, member: &Member, limits: &RaidLimits) -> Result<bool, Error> {
+    if limits.action == RaidAction::None { return Ok(false) }
+
+    let guild_id = member.guild_id;
+    let action = format!("{} a <@{}> por entrar durante un raid", limits.action.name(), member.user.id);
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::Raid, &action).await? { return Ok(false) }
+
+    match limits.action {
+        RaidAction::None => {}
+        RaidAction::Timeout => {
+            let time_out_timer = GuildData::verify_data(guild_id).await?
+                .and_then(|data| data.time_out.time)
+                .and_then(|time| time.parse::<i64>().ok())
+                .unwrap_or(DEFAULT_RAID_TIME_OUT_SECONDS);
+
+            silence_member(&ctx.http, &mut member.clone(), time_out_timer).await?;
+        }
+        RaidAction::Kick => {
+            kick_without_goodbye(&ctx.http, guild_id, member.user.id, "Entrada durante un raid").await?;
+            return Ok(true)
+        }
+    }
+
+    Ok(false)
+}
+
+/// # Esta función envía un aviso al canal de Logs mencionando al rol de administrador
+async fn alert_staff(ctx: &serenity::Context, guild_id: GuildId, count: usize, limits: &RaidLimits, slowmode: bool) -> UnwrapResult<()> {
+    let guild_data = GuildData::verify_data(guild_id).await?.into_result()?;
+    let log_channel = guild_data.channels.logs.into_result()?.parse::<ChannelId>()?;
+    let content = guild_data.admins.role.map(|role| format!("<@&{role}>")).unwrap_or_default();
+    let measures = if slowmode { "Se ha activado el modo lento y se omiten" } else { "Se omiten" };
+
+    let embed = CreateEmbed::default()
+        .title("🚨 Raid detectado")
+        .description(format!(
+            "Han entrado {count} miembros en menos de {} segundos.\n**Acción:** {}\n\
+            {measures} las bienvenidas durante {} minutos. \
+            Usa `/lockdown start` para bloquear los canales.",
+            limits.window.as_secs(),
+            limits.action.name(),
+            RAID_MODE_DURATION.as_secs() / 60
+        ))
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().content(content).embed(embed)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,265 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use serenity::all::{ChannelId, GuildId, UserId};
+
+/// Cantidad de canales distintos por defecto para considerar un mensaje como spam
+pub const DEFAULT_SPAM_CHANNELS: usize = 3;
+
+/// Segundos por defecto entre mensajes repetidos para seguir rastreándolos
+pub const DEFAULT_SPAM_WINDOW_SECONDS: u64 = 5;
+
+/// # Límites para detectar spam
+///
+/// - `channels`: Cantidad de canales distintos con el mismo mensaje para considerarlo spam (mínimo 2)
+/// - `window`: Tiempo máximo entre mensajes repetidos para seguir rastreándolos
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpamThresholds {
+    pub channels: usize,
+    pub window: Duration,
+}
+
+impl Default for SpamThresholds {
+    fn default() -> Self {
+        Self {
+            channels: DEFAULT_SPAM_CHANNELS,
+            window: Duration::from_secs(DEFAULT_SPAM_WINDOW_SECONDS),
+        }
+    }
+}
+
+/// # Resultado de registrar un mensaje en el rastreador
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpamVerdict {
+    /// El mensaje no es spam
+    Clean,
+    /// El mensaje es spam, contiene los canales donde se repitió
+    Spam(Vec<ChannelId>),
+}
+
+/// # Mensaje rastreado de un usuario
+///
+/// - Almacena el contenido del último mensaje
+/// - Almacena los canales donde se repitió
+/// - Almacena el tiempo del último mensaje y la ventana con la que se registró
+#[derive(Debug)]
+struct TrackedMessage {
+    content: Arc<String>,
+    channel_ids: Vec<ChannelId>,
+    last_message_time: Instant,
+    window: Duration,
+}
+
+impl TrackedMessage {
+    fn new(content: Arc<String>, channel_id: ChannelId, now: Instant, window: Duration) -> Self {
+        Self {
+            content,
+            channel_ids: vec![channel_id],
+            last_message_time: now,
+            window,
+        }
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.last_message_time) >= self.window
+    }
+}
+
+/// # Rastreador de spam
+///
+/// - Cada servidor y usuario tiene su propia entrada
+/// - La actividad de un usuario no reinicia la detección de los demás
+/// - `DashMap` divide el mapa en fragmentos con su propio bloqueo, sin un bloqueo global
+#[derive(Debug, Default)]
+pub struct SpamTracker {
+    entries: DashMap<(GuildId, UserId), TrackedMessage>,
+}
+
+impl SpamTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Registra un mensaje y decide si es spam
+    ///
+    /// - Si el mensaje anterior caducó o tenía otro contenido, se empieza a rastrear el nuevo mensaje
+    /// - Si el mensaje se repite en el mismo canal, se deja de rastrear
+    /// - Si el mensaje se repite en suficientes canales distintos, es spam y se deja de rastrear
+    pub fn record(
+        &self,
+        guild_id: GuildId,
+        author_id: UserId,
+        content: &Arc<String>,
+        channel_id: ChannelId,
+        thresholds: SpamThresholds,
+        now: Instant,
+    ) -> SpamVerdict {
+        let key = (guild_id, author_id);
+        let Some(mut tracked) = self.entries.get_mut(&key) else {
+            self.entries.insert(key, TrackedMessage::new(content.clone(), channel_id, now, thresholds.window));
+            return SpamVerdict::Clean
+        };
+
+        if tracked.is_expired(now) || tracked.content != *content {
+            *tracked = TrackedMessage::new(content.clone(), channel_id, now, thresholds.window);
+            return SpamVerdict::Clean
+        }
+
+        if tracked.channel_ids.contains(&channel_id) {
+            drop(tracked);
+            self.entries.remove(&key);
+            return SpamVerdict::Clean
+        }
+
+        tracked.channel_ids.push(channel_id);
+        tracked.last_message_time = now;
+        tracked.window = thresholds.window;
+        if tracked.channel_ids.len() < thresholds.channels {
+            return SpamVerdict::Clean
+        }
+
+        let channel_ids = std::mem::take(&mut tracked.channel_ids);
+        drop(tracked);
+        self.entries.remove(&key);
+
+        SpamVerdict::Spam(channel_ids)
+    }
+
+    /// # Elimina los mensajes rastreados cuya ventana ha caducado
+    pub fn cleanup(&self, now: Instant) {
+        self.entries.retain(|_, tracked| !tracked.is_expired(now));
+    }
+
+    /// Cantidad de usuarios rastreados
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: GuildId = GuildId::new(1);
+    const OTHER_GUILD: GuildId = GuildId::new(2);
+    const USER: UserId = UserId::new(10);
+    const OTHER_USER: UserId = UserId::new(11);
+
+    fn channel(id: u64) -> ChannelId {
+        ChannelId::new(id)
+    }
+
+    fn content(text: &str) -> Arc<String> {
+        Arc::new(text.to_string())
+    }
+
+    #[test]
+    fn detects_spam_after_threshold_channels() {
+        let tracker = SpamTracker::new();
+        let thresholds = SpamThresholds::default();
+        let message = content("https://spam.example");
+        let now = Instant::now();
+
+        assert_eq!(tracker.record(GUILD, USER, &message, channel(1), thresholds, now), SpamVerdict::Clean);
+        assert_eq!(tracker.record(GUILD, USER, &message, channel(2), thresholds, now), SpamVerdict::Clean);
+        assert_eq!(
+            tracker.record(GUILD, USER, &message, channel(3), thresholds, now),
+            SpamVerdict::Spam(vec![channel(1), channel(2), channel(3)])
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn repeating_in_same_channel_resets_only_that_user() {
+        let tracker = SpamTracker::new();
+        let thresholds = SpamThresholds::default();
+        let message = content("https://spam.example");
+        let now = Instant::now();
+
+        tracker.record(GUILD, USER, &message, channel(1), thresholds, now);
+        tracker.record(GUILD, OTHER_USER, &message, channel(1), thresholds, now);
+        tracker.record(GUILD, OTHER_USER, &message, channel(2), thresholds, now);
+
+        assert_eq!(tracker.record(GUILD, USER, &message, channel(1), thresholds, now), SpamVerdict::Clean);
+        assert_eq!(tracker.len(), 1);
+        assert_eq!(
+            tracker.record(GUILD, OTHER_USER, &message, channel(3), thresholds, now),
+            SpamVerdict::Spam(vec![channel(1), channel(2), channel(3)])
+        );
+    }
+
+    #[test]
+    fn different_content_restarts_tracking() {
+        let tracker = SpamTracker::new();
+        let thresholds = SpamThresholds::default();
+        let now = Instant::now();
+
+        tracker.record(GUILD, USER, &content("https://a.example"), channel(1), thresholds, now);
+        tracker.record(GUILD, USER, &content("https://a.example"), channel(2), thresholds, now);
+
+        assert_eq!(tracker.record(GUILD, USER, &content("https://b.example"), channel(3), thresholds, now), SpamVerdict::Clean);
+        assert_eq!(tracker.record(GUILD, USER, &content("https://b.example"), channel(4), thresholds, now), SpamVerdict::Clean);
+    }
+
+    #[test]
+    fn expired_messages_are_not_counted() {
+        let tracker = SpamTracker::new();
+        let thresholds = SpamThresholds::default();
+        let message = content("https://spam.example");
+        let now = Instant::now();
+
+        tracker.record(GUILD, USER, &message, channel(1), thresholds, now);
+        tracker.record(GUILD, USER, &message, channel(2), thresholds, now);
+
+        let later = now + thresholds.window;
+        assert_eq!(tracker.record(GUILD, USER, &message, channel(3), thresholds, later), SpamVerdict::Clean);
+    }
+
+    #[test]
+    fn guilds_are_tracked_separately() {
+        let tracker = SpamTracker::new();
+        let thresholds = SpamThresholds::default();
+        let message = content("https://spam.example");
+        let now = Instant::now();
+
+        tracker.record(GUILD, USER, &message, channel(1), thresholds, now);
+        tracker.record(GUILD, USER, &message, channel(2), thresholds, now);
+
+        assert_eq!(tracker.record(OTHER_GUILD, USER, &message, channel(3), thresholds, now), SpamVerdict::Clean);
+        assert_eq!(tracker.len(), 2);
+    }
+
+    #[test]
+    fn custom_thresholds_are_respected() {
+        let tracker = SpamTracker::new();
+        let thresholds = SpamThresholds { channels: 2, window: Duration::from_secs(30) };
+        let message = content("https://spam.example");
+        let now = Instant::now();
+
+        tracker.record(GUILD, USER, &message, channel(1), thresholds, now);
+        assert_eq!(
+            tracker.record(GUILD, USER, &message, channel(2), thresholds, now + Duration::from_secs(20)),
+            SpamVerdict::Spam(vec![channel(1), channel(2)])
+        );
+    }
+
+    #[test]
+    fn cleanup_removes_expired_entries() {
+        let tracker = SpamTracker::new();
+        let message = content("https://spam.example");
+        let now = Instant::now();
+
+        tracker.record(GUILD, USER, &message, channel(1), SpamThresholds::default(), now);
+        tracker.record(GUILD, OTHER_USER, &message, channel(1), SpamThresholds { channels: 3, window: Duration::from_secs(60) }, now);
+
+        tracker.cleanup(now + Duration::from_secs(10));
+        assert_eq!(tracker.len(), 1);
+    }
+}
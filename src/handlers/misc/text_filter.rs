@@ -0,0 +1,228 @@
+use std::sync::LazyLock;
+
+use poise::ChoiceParameter;
+use poise::serenity_prelude as serenity;
+use regex::Regex;
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, GuildId, Message};
+use unicode_normalization::char::is_combining_mark;
+
+use crate::commands::moderation::filter::FilterAction;
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::word_filter::apply_filter_action;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+/// Mensaje de advertencia al enviar un mensaje con demasiadas mayúsculas, emojis o texto zalgo
+pub const DEFAULT_TEXT_FILTER_WARN_MESSAGE: &str = "{user} Evita enviar mensajes con demasiadas mayúsculas, emojis o caracteres extraños";
+
+const DEFAULT_CAPS_RATIO: f64 = 0.7;
+const DEFAULT_CAPS_MIN_LENGTH: usize = 10;
+const DEFAULT_EMOJI_MAX: usize = 10;
+const DEFAULT_ZALGO_RATIO: f64 = 0.3;
+const DEFAULT_ZALGO_MIN_LENGTH: usize = 5;
+
+/// # Expresión regular de los emojis personalizados de Discord (`<:nombre:id>` y `<a:nombre:id>`)
+static CUSTOM_EMOJI_REGEX: LazyLock<Option<Regex>> = LazyLock::new(|| {
+    Regex::new(r"<a?:\w+:\d+>").ok()
+});
+
+/// # Límites de los filtros de texto configurados en el servidor
+struct TextLimits {
+    enabled: bool,
+    action: FilterAction,
+    caps_ratio: f64,
+    caps_min_length: usize,
+    emoji_max: usize,
+    zalgo_ratio: f64,
+    zalgo_min_length: usize,
+    exempt_channels: Vec<ChannelId>,
+}
+
+impl TextLimits {
+    async fn from_guild(guild_id: GuildId) -> UnwrapResult<Self> {
+        let text_filter = GuildData::verify_data(guild_id).await?
+            .map(|data| data.text_filter)
+            .unwrap_or_default();
+
+        Ok(Self {
+            enabled: text_filter.enabled.as_deref() == Some("true"),
+            action: text_filter.action.as_deref().map_or(FilterAction::Delete, FilterAction::from),
+            caps_ratio: text_filter.caps_ratio
+                .and_then(|value| value.parse::<f64>().ok())
+                .map_or(DEFAULT_CAPS_RATIO, |percent| percent / 100.0),
+            caps_min_length: text_filter.caps_min_length
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_CAPS_MIN_LENGTH),
+            emoji_max: text_filter.emoji_max
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_EMOJI_MAX),
+            zalgo_ratio: text_filter.zalgo_ratio
+                .and_then(|value| value.parse::<f64>().ok())
+                .map_or(DEFAULT_ZALGO_RATIO, |percent| percent / 100.0),
+            zalgo_min_length: text_filter.zalgo_min_length
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(DEFAULT_ZALGO_MIN_LENGTH),
+            exempt_channels: text_filter.exempt_channels
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|channel_id| channel_id.parse().ok())
+                .collect(),
+        })
+    }
+}
+
+/// # Motivo por el que un mensaje no pasa los filtros de texto
+enum TextViolation {
+    Caps(f64),
+    Emoji(usize),
+    Zalgo(f64),
+}
+
+impl TextViolation {
+    fn describe(&self) -> String {
+        match self {
+            Self::Caps(ratio) => format!("un {:.0}% de mayúsculas", ratio * 100.0),
+            Self::Emoji(count) => format!("{count} emojis"),
+            Self::Zalgo(ratio) => format!("un {:.0}% de caracteres combinados (texto zalgo)", ratio * 100.0),
+        }
+    }
+}
+
+/// Elimina los emojis personalizados para que sus nombres no cuenten como texto
+fn strip_custom_emojis(text: &str) -> String {
+    CUSTOM_EMOJI_REGEX
+        .as_ref()
+        .map_or_else(|| text.to_string(), |emoji_re| emoji_re.replace_all(text, "").into_owned())
+}
+
+/// Comprueba si un carácter es un emoji Unicode
+const fn is_emoji(character: char) -> bool {
+    matches!(character as u32, 0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF)
+}
+
+/// # Calcula la proporción de mayúsculas de un texto
+///
+/// - Solo se cuentan las letras que tienen mayúscula y minúscula
+/// - Devuelve `None` si el texto tiene menos letras que el mínimo
+pub fn caps_ratio(text: &str, min_length: usize) -> Option<f64> {
+    let letters = text
+        .chars()
+        .filter(|character| character.is_uppercase() || character.is_lowercase())
+        .collect::<Vec<_>>();
+
+    if letters.is_empty() || letters.len() < min_length { return None }
+    let uppercase = letters.iter().filter(|character| character.is_uppercase()).count();
+
+    Some(uppercase as f64 / letters.len() as f64)
+}
+
+/// # Cuenta los emojis Unicode y personalizados de un texto
+pub fn emoji_count(text: &str) -> usize {
+    let custom = CUSTOM_EMOJI_REGEX
+        .as_ref()
+        .map_or(0, |emoji_re| emoji_re.find_iter(text).count());
+
+    custom + text.chars().filter(|character| is_emoji(*character)).count()
+}
+
+/// # Calcula la proporción de caracteres combinados de un texto
+///
+/// - El texto zalgo apila muchos acentos y marcas sobre cada letra
+/// - Devuelve `None` si el texto tiene menos caracteres visibles que el mínimo
+pub fn combining_ratio(text: &str, min_length: usize) -> Option<f64> {
+    let visible = text.chars().filter(|character| !character.is_whitespace()).collect::<Vec<_>>();
+    let combining = visible.iter().filter(|character| is_combining_mark(**character)).count();
+    let base = visible.len() - combining;
+
+    if base == 0 || base < min_length { return None }
+
+    Some(combining as f64 / visible.len() as f64)
+}
+
+/// # Revisa un mensaje con los límites del servidor
+fn find_violation(content: &str, limits: &TextLimits) -> Option<TextViolation> {
+    let emojis = emoji_count(content);
+    if emojis > limits.emoji_max { return Some(TextViolation::Emoji(emojis)) }
+
+    let text = strip_custom_emojis(content);
+    if let Some(ratio) = combining_ratio(&text, limits.zalgo_min_length).filter(|ratio| *ratio > limits.zalgo_ratio) {
+        return Some(TextViolation::Zalgo(ratio))
+    }
+
+    caps_ratio(&text, limits.caps_min_length)
+        .filter(|ratio| *ratio > limits.caps_ratio)
+        .map(TextViolation::Caps)
+}
+
+/// # Esta función aplica los filtros de mayúsculas, emojis y texto zalgo
+///
+/// - Los canales exentos y los administradores no se revisan
+/// - Se aplica la acción configurada mediante el sistema de advertencias o silencio
+/// - Devuelve `true` si el mensaje fue borrado
+pub async fn check_text_filter(
+    ctx: &serenity::Context,
+    message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+) -> Result<bool, Error> {
+    let limits = TextLimits::from_guild(guild_id).await?;
+    if !limits.enabled || limits.exempt_channels.contains(&message.channel_id) { return Ok(false) }
+
+    let Some(violation) = find_violation(&message.content, &limits) else { return Ok(false) };
+    let mut member = guild_id.member(&ctx.http, message.author.id).await?;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) { return Ok(false) }
+
+    let action = format!(
+        "{} el mensaje de <@{}> en <#{}> por contener {}",
+        limits.action.name(),
+        message.author.id,
+        message.channel_id,
+        violation.describe()
+    );
+
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::TextFilter, &action).await? { return Ok(false) }
+
+    let reason = format!("por enviar un mensaje con {}", violation.describe());
+    apply_filter_action(ctx, message, &mut member, limits.action, DEFAULT_TEXT_FILTER_WARN_MESSAGE, &reason).await?;
+    log_text_violation(ctx, guild_id, message, &limits.action, &violation).await?;
+
+    Ok(limits.action != FilterAction::Log)
+}
+
+/// # Esta función envía un `Embed` al canal de Logs con el mensaje filtrado
+async fn log_text_violation(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    message: &Message,
+    action: &FilterAction,
+    violation: &TextViolation,
+) -> UnwrapResult<()> {
+    let log_channel = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let author = &message.author;
+    let embed = CreateEmbed::default()
+        .title("🔠 Filtro de texto")
+        .author(CreateEmbedAuthor::new(author.name.clone())
+            .icon_url(author.face()))
+        .description(format!(
+            "El mensaje de <@{}> contiene {}.\n**Canal:** <#{}>\n**Acción:** {}\n**Mensaje:** {}",
+            author.id,
+            violation.describe(),
+            message.channel_id,
+            action.name(),
+            message.content
+        ))
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
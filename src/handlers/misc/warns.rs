@@ -1,26 +1,145 @@
 use std::collections::HashMap;
 use std::sync::Arc;
-use chrono::{Duration, Utc};
-use serenity::all::{Http, Member, Mentionable, Message, Timestamp};
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, GuildId, Http, Member, Message, UserId};
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::punishment::silence_member;
 use crate::utils::{CommandResult, Warns};
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+use crate::utils::embeds::send_warn_embed;
+use crate::utils::template::{format_duration, upgrade_legacy, TemplateContext};
+
+/// Cantidad de advertencias con la que se silencia al usuario
+pub const MAX_WARNS: u8 = 3;
+
+/// Mensaje de silencio por defecto para las sanciones que no son por menciones
+pub const DEFAULT_WARN_TIME_OUT_MESSAGE: &str = "{mention} Has sido silenciado por acumular {max_warns} advertencias";
 
 pub async fn handle_warn_system(
     member: &mut Member,
-    new_message: &Message,
+    channel_id: ChannelId,
     mut message_map: HashMap<&str, String>,
     http: &Arc<Http>,
     mut warns: Warns,
     time_out_timer: i64,
     time_out_message: String,
 ) -> CommandResult {
+    silence_member(http, member, time_out_timer).await?;
 
-    let try_seconds = Duration::try_seconds(time_out_timer);
-    let time = Timestamp::from(Utc::now() + try_seconds.unwrap_or_default());
-    member.disable_communication_until_datetime(&http, time).await?;
-
-    message_map.insert("content", format!("{} {}", member.mention(), time_out_message));
-    http.send_message(new_message.channel_id, vec![], &message_map).await?;
+    message_map.insert("content", time_out_message);
+    http.send_message(channel_id, vec![], &message_map).await?;
     warns.reset_warns().await?;
 
     Ok(())
 }
+
+/// # Intercepta una advertencia si el módulo de advertencias está en modo de prueba
+///
+/// - Registra en el canal de Logs la advertencia y, si se llegaría a `MAX_WARNS`, el silencio
+/// - Las advertencias guardadas no cambian y no se envía el `Embed` de advertencia
+/// - Devuelve `true` si la advertencia fue interceptada y no debe aplicarse
+pub async fn intercept_warn(
+    http: &Http,
+    guild_id: GuildId,
+    user_id: UserId,
+    channel_id: ChannelId,
+    time_out_timer: i64,
+) -> UnwrapResult<bool> {
+    if !dry_run::is_dry_run(guild_id, DryRunModule::Warns).await? { return Ok(false) }
+
+    let warns = Warns::new(user_id).get_warns().await?.map_or(0, |warns| warns.warns) + 1;
+    let mut action = format!("Advertir a <@{user_id}> en <#{channel_id}> ({warns}/{MAX_WARNS} advertencias)");
+    if warns >= MAX_WARNS {
+        action.push_str(&format!(" y silenciarle durante {}", format_duration(time_out_timer)));
+    }
+
+    dry_run::intercept(http, guild_id, DryRunModule::Warns, &action).await
+}
+
+/// # Suma una advertencia al usuario y la guarda en la base de datos
+pub async fn warns_counter(warns: &mut Warns, existing_warns: Option<Warns>) -> CommandResult {
+    if let Some(mut existing_warns) = existing_warns {
+        existing_warns.warns += 1;
+        existing_warns.add_warn().await?;
+        *warns = existing_warns;
+    } else {
+        warns.warns += 1;
+        warns.save_to_db().await?;
+    }
+
+    Ok(())
+}
+
+/// # Aplica el flujo de advertencias habitual a un mensaje
+///
+/// - Suma una advertencia al autor y envía el `Embed` de advertencia con `warn_template`
+/// - Si el autor llega a `MAX_WARNS`, se le silencia con el mensaje de silencio del servidor
+/// - Borra el mensaje, salvo que el borrado esté en modo de prueba
+/// - `reason` describe el motivo del borrado en el canal de Logs
+pub async fn warn_and_delete(
+    ctx: &serenity::Context,
+    new_message: &Message,
+    guild_id: GuildId,
+    warn_template: &str,
+    reason: &str,
+) -> CommandResult {
+    let author_user_id = new_message.author.id;
+    let channel_id = new_message.channel_id;
+    let mut member = guild_id.member(&ctx.http, author_user_id).await?;
+    warn_member(ctx, &mut member, channel_id, warn_template).await?;
+
+    let action = format!("Borrar el mensaje de <@{author_user_id}> en <#{channel_id}> {reason}");
+    if !dry_run::intercept(&ctx.http, guild_id, DryRunModule::Deletions, &action).await? {
+        ghost_ping::ignore_deletion(new_message.id);
+        new_message.delete(&ctx.http).await?;
+    }
+
+    Ok(())
+}
+
+/// # Suma una advertencia a un miembro sin borrar ningún mensaje
+///
+/// - Envía el `Embed` de advertencia con `warn_template` en `channel_id`
+/// - Si el miembro llega a `MAX_WARNS`, se le silencia con el mensaje de silencio del servidor
+/// - En modo de prueba solo se registra la advertencia en el canal de Logs
+pub async fn warn_member(
+    ctx: &serenity::Context,
+    member: &mut Member,
+    channel_id: ChannelId,
+    warn_template: &str,
+) -> CommandResult {
+    let guild_id = member.guild_id;
+    let guild_data = GuildData::verify_data(guild_id).await?.into_result()?;
+    let time_out_timer = guild_data.time_out.time
+        .into_result()?
+        .parse::<i64>()?;
+
+    let time_out_template = guild_data.messages.time_out
+        .map_or_else(|| DEFAULT_WARN_TIME_OUT_MESSAGE.to_string(), |time_out| upgrade_legacy(&time_out, "{mention} "));
+
+    if intercept_warn(&ctx.http, guild_id, member.user.id, channel_id, time_out_timer).await? {
+        return Ok(())
+    }
+
+    let mut warns = Warns::new(member.user.id);
+    let existing_warns = warns.get_warns().await?;
+    warns_counter(&mut warns, existing_warns).await?;
+
+    let template_context = TemplateContext::for_member(ctx, member)
+        .channel_id(channel_id)
+        .duration(format_duration(time_out_timer))
+        .max_warns(MAX_WARNS)
+        .warns(warns.warns);
+
+    let warn_message = template_context.render(warn_template);
+    let time_out_message = template_context.render(&time_out_template);
+    send_warn_embed(ctx, guild_id, warns.warns, channel_id, &warn_message, &template_context).await?;
+    if warns.warns >= MAX_WARNS {
+        handle_warn_system(member, channel_id, HashMap::new(), &ctx.http, warns, time_out_timer, time_out_message).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use dashmap::DashMap;
+use poise::ChoiceParameter;
+use poise::serenity_prelude as serenity;
+use regex::{Regex, RegexBuilder};
+use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor, CreateMessage, GuildId, Member, Message};
+use unicode_normalization::UnicodeNormalization;
+use unicode_normalization::char::is_combining_mark;
+
+use crate::log_handle;
+use crate::commands::moderation::filter::{FilterAction, FilterKind, FilterRule};
+use crate::commands::moderation::setters::set_dry_run::DryRunModule;
+use crate::handlers::misc::dry_run;
+use crate::handlers::misc::exceptions::check_admin_exception;
+use crate::handlers::misc::ghost_ping;
+use crate::handlers::misc::punishment::silence_member;
+use crate::handlers::misc::warns::warn_and_delete;
+use crate::utils::Error;
+use crate::utils::config::GuildData;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+/// Mensaje de advertencia al enviar una palabra prohibida
+pub const DEFAULT_FILTER_WARN_MESSAGE: &str = "{user} Tu mensaje contiene una palabra prohibida";
+
+/// Tamaño máximo de una expresión regular compilada, para evitar reglas demasiado costosas
+const MAX_REGEX_SIZE: usize = 1 << 20;
+
+/// # Expresiones regulares compiladas de cada servidor
+///
+/// - Se compilan la primera vez que se usan y se descartan al cambiar las reglas con `/filter`
+static REGEX_CACHE: LazyLock<DashMap<GuildId, HashMap<String, Regex>>> = LazyLock::new(DashMap::new);
+
+/// Comprueba si un carácter es invisible (espacios de ancho cero, guiones suaves, etc.)
+const fn is_invisible(character: char) -> bool {
+    matches!(character, '\u{00AD}' | '\u{180E}' | '\u{200B}'..='\u{200F}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}')
+}
+
+/// Convierte los caracteres más usados en leetspeak a su letra
+const fn unleet(character: char) -> char {
+    match character {
+        '0' => 'o',
+        '1' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        _ => character,
+    }
+}
+
+/// # Normaliza un texto antes de compararlo con las reglas
+///
+/// - Se eliminan los acentos (`canción` -> `cancion`) y los caracteres invisibles
+/// - Se pasa a minúsculas y se deshace el leetspeak (`h0l4` -> `hola`)
+/// - Los espacios seguidos se reducen a uno
+pub fn normalize_text(text: &str) -> String {
+    let mut normalized = String::with_capacity(text.len());
+    let characters = text
+        .nfkd()
+        .filter(|character| !is_combining_mark(*character) && !is_invisible(*character))
+        .flat_map(char::to_lowercase)
+        .map(unleet);
+
+    for character in characters {
+        if character.is_whitespace() {
+            if !normalized.is_empty() && !normalized.ends_with(' ') {
+                normalized.push(' ');
+            }
+            continue
+        }
+        normalized.push(character);
+    }
+
+    normalized.trim_end().to_string()
+}
+
+/// # Texto de un mensaje preparado para compararlo con las reglas
+///
+/// - Las palabras se comparan con el texto normalizado
+/// - Las expresiones regulares se comparan también con el texto original en minúsculas,
+///   porque la normalización cambia los números y símbolos (`\d{16}` no coincidiría nunca)
+pub struct FilterText {
+    pub raw: String,
+    pub normalized: String,
+}
+
+impl FilterText {
+    pub fn new(text: &str) -> Self {
+        Self { raw: text.to_lowercase(), normalized: normalize_text(text) }
+    }
+}
+
+/// # Compila la expresión regular de una regla
+///
+/// - Se compara sin distinguir mayúsculas
+pub fn compile_rule_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(MAX_REGEX_SIZE)
+        .build()
+}
+
+/// # Busca una palabra o frase completa dentro de un texto normalizado
+///
+/// - Solo coincide si no está pegada a otras letras, para no sancionar palabras que la contienen
+fn contains_word(text: &str, word: &str) -> bool {
+    if word.is_empty() { return false }
+
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// # Obtiene la expresión regular compilada de una regla
+///
+/// - Las expresiones que no compilan no se guardan, y la regla no coincide con nada
+fn cached_regex(rule: &FilterRule) -> Option<Regex> {
+    if let Some(regex) = REGEX_CACHE.get(&rule.guild_id).and_then(|regexes| regexes.get(&rule.pattern).cloned()) {
+        return Some(regex)
+    }
+
+    let regex = compile_rule_regex(&rule.pattern).ok()?;
+    REGEX_CACHE.entry(rule.guild_id).or_default().insert(rule.pattern.clone(), regex.clone());
+
+    Some(regex)
+}
+
+/// Descarta las expresiones regulares compiladas de un servidor tras cambiar sus reglas
+pub fn invalidate_regex_cache(guild_id: GuildId) {
+    REGEX_CACHE.remove(&guild_id);
+}
+
+/// # Comprueba si una regla coincide con un texto
+pub fn rule_matches(rule: &FilterRule, text: &FilterText) -> bool {
+    match rule.kind {
+        FilterKind::Literal => contains_word(&text.normalized, &normalize_text(&rule.pattern)),
+        FilterKind::Regex => cached_regex(rule).is_some_and(|regex| regex.is_match(&text.raw) || regex.is_match(&text.normalized)),
+    }
+}
+
+/// # Devuelve la regla más severa que coincide con un texto
+pub fn strictest_match<'a>(rules: &'a [FilterRule], text: &str) -> Option<&'a FilterRule> {
+    let text = FilterText::new(text);
+    rules
+        .iter()
+        .filter(|rule| rule_matches(rule, &text))
+        .max_by_key(|rule| rule.action)
+}
+
+/// # Esta función revisa un mensaje con las reglas del filtro de palabras
+///
+/// - Se aplica la acción de la regla más severa que coincida
+/// - Los administradores están exentos
+/// - Devuelve `true` si el mensaje fue borrado
+pub async fn check_word_filter(
+    ctx: &serenity::Context,
+    message: &Message,
+    guild_id: GuildId,
+    admin_role_id: &Option<String>,
+) -> Result<bool, Error> {
+    let rules = FilterRule::get_rules(guild_id).await?;
+    let Some(rule) = strictest_match(&rules, &message.content) else { return Ok(false) };
+
+    let mut member = guild_id.member(&ctx.http, message.author.id).await?;
+    if check_admin_exception(admin_role_id.clone(), &member, ctx) { return Ok(false) }
+
+    let action = format!(
+        "{} el mensaje de <@{}> en <#{}> por la regla `{}`",
+        rule.action.name(),
+        message.author.id,
+        message.channel_id,
+        rule.pattern
+    );
+
+    if dry_run::intercept(&ctx.http, guild_id, DryRunModule::WordFilter, &action).await? { return Ok(false) }
+
+    let reason = format!("por la regla del filtro de palabras `{}`", rule.pattern);
+    apply_filter_action(ctx, message, &mut member, rule.action, DEFAULT_FILTER_WARN_MESSAGE, &reason).await?;
+    // Sin canal de Logs la acción ya está aplicada, por lo que no debe cortar el resto de revisiones
+    if let Err(why) = log_filter_match(ctx, guild_id, message, rule).await {
+        log_handle!("No se pudo registrar la coincidencia del filtro de palabras: {why}");
+    }
+
+    Ok(rule.action != FilterAction::Log)
+}
+
+/// # Aplica la acción de un filtro a un mensaje
+///
+/// - `Warn` utiliza el sistema de advertencias, por lo que puede acabar en silencio
+/// - `Timeout` utiliza el tiempo de silencio configurado en el servidor
+pub async fn apply_filter_action(
+    ctx: &serenity::Context,
+    message: &Message,
+    member: &mut Member,
+    action: FilterAction,
+    warn_message: &str,
+    reason: &str,
+) -> Result<(), Error> {
+    let guild_id = member.guild_id;
+    match action {
+        FilterAction::Log => {}
+        FilterAction::Delete => {
+            ghost_ping::ignore_deletion(message.id);
+            message.delete(&ctx.http).await?;
+        }
+        FilterAction::Warn => warn_and_delete(ctx, message, guild_id, warn_message, reason).await?,
+        FilterAction::Timeout => {
+            let time_out_timer = GuildData::verify_data(guild_id).await?
+                .into_result()?
+                .time_out
+                .time
+                .into_result()?
+                .parse::<i64>()?;
+
+            silence_member(&ctx.http, member, time_out_timer).await?;
+            ghost_ping::ignore_deletion(message.id);
+            message.delete(&ctx.http).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// # Esta función envía un `Embed` al canal de Logs con la regla que coincidió
+async fn log_filter_match(
+    ctx: &serenity::Context,
+    guild_id: GuildId,
+    message: &Message,
+    rule: &FilterRule,
+) -> UnwrapResult<()> {
+    let log_channel = GuildData::verify_data(guild_id).await?
+        .into_result()?
+        .channels
+        .logs
+        .into_result()?
+        .parse::<ChannelId>()?;
+
+    let author = &message.author;
+    let embed = CreateEmbed::default()
+        .title("🚫 Filtro de palabras")
+        .author(CreateEmbedAuthor::new(author.name.clone())
+            .icon_url(author.face()))
+        .description(format!(
+            "El mensaje de <@{}> coincide con la regla `{}` ({}).\n**Canal:** <#{}>\n**Acción:** {}\n**Mensaje:** {}",
+            author.id,
+            rule.pattern,
+            rule.kind.name(),
+            message.channel_id,
+            rule.action.name(),
+            message.content
+        ))
+        .color(0x00ff_0000);
+
+    log_channel.send_message(&ctx.http, CreateMessage::default().embed(embed)).await?;
+
+    Ok(())
+}
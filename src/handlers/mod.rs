@@ -1,7 +1,9 @@
 pub mod error;
 pub mod misc;
 pub mod welcome;
+pub mod goodbye;
 pub mod messages;
 pub mod events;
 pub mod typing_start;
-pub mod interactions;
\ No newline at end of file
+pub mod interactions;
+pub mod member_join;
\ No newline at end of file
@@ -1,14 +1,20 @@
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
+use chrono::Utc;
 use tokio::time::{Duration, sleep};
 
-use serenity::all::{ChannelId, GuildId, TypingStartEvent, UserId};
+use serenity::all::{ChannelId, GuildId, Http, TypingStartEvent, UserId};
 use tokio::sync::Mutex;
 use crate::commands::moderation::setters::set_forbidden_exception::ForbiddenException;
+use crate::handlers::misc::native_automod::resync_native_automod;
 
+use crate::log_handle;
 use crate::utils::CommandResult;
 use crate::utils::config::GuildData;
-use crate::utils::debug::IntoUnwrapResult;
+use crate::utils::debug::{IntoUnwrapResult, UnwrapResult};
+
+/// Duración por defecto de la excepción dinámica si el servidor no ha establecido una
+const DEFAULT_EXCEPTION_SECONDS: u64 = 300;
 
 type TimerMap<T> = LazyLock<Arc<Mutex<HashMap<T, tokio::task::JoinHandle<()>>>>>;
 
@@ -19,11 +25,13 @@ static TIMERS: TimerMap<UserId> = LazyLock::new(|| {
 /// # Esta función maneja el evento de inicio de escritura de un usuario
 /// - Verifica si el usuario que está escribiendo es el usuario prohibido de tagear `(@)`
 /// - Verifica si el canal en el que está escribiendo es el canal de excepciones `(#🌱meica-chat)`
-/// - Si se cumplen las condiciones anteriores, activa la excepción de tageo durante 5 minutos
+/// - Si se cumplen las condiciones anteriores, activa la excepción de tageo durante el tiempo
+///     establecido en `time_out.exception` (5 minutos por defecto)
+/// - Las menciones al usuario dejan de bloquearse en el AutoMod de Discord mientras dure la excepción
 /// 
 /// Nota: Se debe mejorar la forma de obtener el `forbidden_user_id`. Por ahora, solo puede haber
 /// un usuario al cual aplicar excepciones.
-pub async fn handler(event: &TypingStartEvent) -> CommandResult {
+pub async fn handler(http: &Arc<Http>, event: &TypingStartEvent) -> CommandResult {
     let user_id = event.user_id;
     let channel_id = event.channel_id;
     let guild_id = event.guild_id.into_result()?;
@@ -44,14 +52,68 @@ pub async fn handler(event: &TypingStartEvent) -> CommandResult {
         .parse::<ChannelId>()?;
 
     if user_id == forbidden_user_id && channel_id == exception_channel_id {
+        let exception_seconds = GuildData::verify_data(guild_id).await?
+            .into_result()?
+            .time_out
+            .exception
+            .and_then(|seconds| seconds.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_EXCEPTION_SECONDS);
+
         ForbiddenException::manual_switch(user_id, guild_id, true).await?;
-        exception_timer(user_id, guild_id, Duration::from_secs(300)).await;
+        exception_timer(http.clone(), user_id, guild_id, Duration::from_secs(exception_seconds)).await?;
+        resync_native_automod(http, guild_id).await;
     }
 
     Ok(())
 }
 
-pub async fn exception_timer(user_id: UserId, guild_id: GuildId, duration: Duration) {
+/// # Programa la desactivación de la excepción dinámica
+///
+/// - Guarda en la Base de Datos el momento en el que expira la excepción
+///     para poder reprogramarla si el Bot se reinicia
+pub async fn exception_timer(http: Arc<Http>, user_id: UserId, guild_id: GuildId, duration: Duration) -> UnwrapResult<()> {
+    let expires_at = Utc::now().timestamp() + i64::try_from(duration.as_secs()).unwrap_or(i64::MAX);
+    ForbiddenException::set_expiration(user_id, guild_id, Some(expires_at)).await?;
+    arm_timer(http, user_id, guild_id, duration).await;
+
+    Ok(())
+}
+
+/// # Cancela la desactivación programada de la excepción (por ejemplo, al cambiarla manualmente)
+///
+/// - Se llama después de guardar el nuevo estado, para sincronizarlo con el AutoMod de Discord
+pub async fn cancel_exception_timer(http: &Arc<Http>, user_id: UserId, guild_id: GuildId) {
+    if let Some(handle) = TIMERS.lock().await.remove(&user_id) {
+        handle.abort();
+    }
+
+    resync_native_automod(http, guild_id).await;
+}
+
+/// # Reprograma las excepciones dinámicas guardadas en la Base de Datos
+///
+/// - Se ejecuta al iniciar el Bot
+/// - Si la excepción ya expiró mientras el Bot estaba apagado, se desactiva inmediatamente
+/// - Si la excepción sigue vigente, se programa su desactivación con el tiempo restante
+pub async fn rearm_exception_timers(http: Arc<Http>) -> UnwrapResult<()> {
+    let pending = ForbiddenException::get_pending_expirations().await?;
+    let now = Utc::now().timestamp();
+
+    for exception in pending {
+        let Some(expires_at) = exception.expires_at else { continue };
+        let remaining = u64::try_from(expires_at - now).unwrap_or_default();
+        if remaining == 0 {
+            expire_exception(&http, exception.user_id, exception.guild_id).await;
+            continue
+        }
+
+        arm_timer(http.clone(), exception.user_id, exception.guild_id, Duration::from_secs(remaining)).await;
+    }
+
+    Ok(())
+}
+
+async fn arm_timer(http: Arc<Http>, user_id: UserId, guild_id: GuildId, duration: Duration) {
     let timers = TIMERS.clone();
     let mut timers_lock = timers.lock().await;
     
@@ -61,10 +123,22 @@ pub async fn exception_timer(user_id: UserId, guild_id: GuildId, duration: Durat
     
     let handle = tokio::spawn(async move {
         sleep(duration).await;
-
-        let _ = ForbiddenException::manual_switch(user_id, guild_id, false).await;
+        expire_exception(&http, user_id, guild_id).await;
     });
     
     timers_lock.insert(user_id, handle);
     drop(timers_lock);
+}
+
+/// # Desactiva la excepción y vuelve a bloquear las menciones en el AutoMod de Discord
+async fn expire_exception(http: &Arc<Http>, user_id: UserId, guild_id: GuildId) {
+    if let Err(why) = ForbiddenException::manual_switch(user_id, guild_id, false).await {
+        log_handle!("Could not disable the forbidden exception: {why}");
+    }
+
+    if let Err(why) = ForbiddenException::set_expiration(user_id, guild_id, None).await {
+        log_handle!("Could not clear the forbidden exception expiration: {why}");
+    }
+
+    resync_native_automod(http, guild_id).await;
 }
\ No newline at end of file
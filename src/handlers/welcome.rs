@@ -1,23 +1,39 @@
 use std::fs::remove_file;
 use std::collections::HashMap;
+use std::path::Path;
 
 use reqwest::get;
-use image::DynamicImage;
+use image::{imageops, DynamicImage, Rgba};
+use imageproc::drawing::draw_text_mut;
 use poise::serenity_prelude as serenity;
 use plantita_welcomes::create_welcome::combine_images;
-use serenity::all::{ChannelId, CreateAttachment, User};
+use rusttype::{Font, Scale};
+use serenity::all::{ChannelId, CreateAttachment, Member, User};
 use crate::location;
 
+use crate::commands::moderation::welcome::{AvatarShape, WelcomeText};
 use crate::utils::CommandResult;
-use crate::utils::config::GuildData;
-use crate::utils::debug::{IntoUnwrapResult, UnwrapErrors, UnwrapLog};
+use crate::utils::config::{GuildData, WelcomeCard};
+use crate::utils::debug::{IntoUnwrapResult, UnwrapLog, UnwrapResult};
+use crate::utils::embeds::parse_hex_color;
+use crate::utils::template::{upgrade_legacy, TemplateContext};
+
+/// Saludo que se añadía antes de los mensajes de bienvenida guardados sin variables
+pub const LEGACY_WELCOME_PREFIX: &str = "Bienvenido {mention} a {server}. \n";
+
+/// Fondo de la tarjeta de bienvenida si el servidor no ha subido uno propio
+pub const DEFAULT_WELCOME_BACKGROUND: &str = "assets/background.png";
+
+/// Posición y tamaño del avatar en el fondo por defecto
+const DEFAULT_AVATAR_X: u32 = 74;
+const DEFAULT_AVATAR_Y: u32 = 74;
+const DEFAULT_AVATAR_SIZE: u32 = 372;
 
 pub async fn handler(
     ctx: &serenity::Context,
     new_member: &serenity::Member,
 ) -> CommandResult {
     let guild_id = new_member.guild_id;
-    let user = &new_member.user;
     let channel_id = GuildData::verify_data(guild_id).await?
         .unwrap_log(location!())?
         .channels
@@ -25,17 +41,21 @@ pub async fn handler(
         .into_result()?
         .parse::<ChannelId>()?;
 
-    let welcome_message = GuildData::verify_data(guild_id).await?
+    let welcome_template = GuildData::verify_data(guild_id).await?
         .unwrap_log(location!())?
         .messages
         .welcome
         .into_result()?;
 
-    let mut background = image::open("assets/background.png")?;
-    let file = get_welcome_attachment(&mut background, user, 74, 74, 372).await?;
+    let welcome_template = upgrade_legacy(&welcome_template, LEGACY_WELCOME_PREFIX);
+    let welcome_message = TemplateContext::for_member(ctx, new_member)
+        .channel_id(channel_id)
+        .render(&welcome_template);
+
+    let file = render_welcome_card(ctx, new_member).await?;
 
     let mut message_map = HashMap::new();
-    message_map.insert("content", format!("Bienvenido {user} a {}. \n{welcome_message}", guild_id.name(&ctx.cache).unwrap_or_default()));
+    message_map.insert("content", welcome_message);
     let http = &ctx.http;
     let attachment = CreateAttachment::path(&file).await?;
 
@@ -48,22 +68,92 @@ pub async fn handler(
     Ok(())
 }
 
-async fn get_welcome_attachment(background: &mut DynamicImage, user: &User, x: u32, y: u32, avatar_size: u32) -> Result<String, UnwrapErrors> {
-    // Obtén la URL del avatar del usuario
-    let avatar_url = user.face();
+/// # Genera la tarjeta de bienvenida de un miembro con la plantilla del servidor
+///
+/// - Devuelve la ruta de la imagen generada, que se debe borrar después de enviarla
+pub async fn render_welcome_card(ctx: &serenity::Context, member: &Member) -> UnwrapResult<String> {
+    let guild_id = member.guild_id;
+    let card = GuildData::verify_data(guild_id).await?
+        .map(|data| data.welcome_card)
+        .unwrap_or_default();
 
-    // Descarga la imagen del avatar
-    let response = get(avatar_url).await?;
-    let bytes= response.bytes().await?;
+    let texts = WelcomeText::get_texts(guild_id).await?
+        .into_iter()
+        .filter(|line| !line.goodbye)
+        .collect::<Vec<_>>();
 
-    // Carga la imagen del avatar en memoria y redimensiona a 256x256
-    let img = image::load_from_memory(&bytes)?;
-    img.resize(256, 256, image::imageops::Lanczos3);
+    let template_context = TemplateContext::for_member(ctx, member);
+    let output_path = format!("/tmp/{}_welcome.png", member.user.id);
 
-    // Guarda la imagen del avatar en un archivo temporal
-    let output_path = format!("/tmp/{}_welcome.png", user.id);
-    combine_images(background, &img, x, y, avatar_size)?;
-    background.save(&output_path)?;
+    render_card(&card, &texts, &template_context, &member.user, DEFAULT_WELCOME_BACKGROUND, &output_path).await?;
 
     Ok(output_path)
-}
\ No newline at end of file
+}
+
+/// # Genera una tarjeta con el avatar del usuario y las líneas de texto
+///
+/// - Sin fondo propio, se usa `default_background`
+/// - Sin forma configurada, el avatar se pega con `combine_images` de `plantita_welcomes`,
+///   igual que la tarjeta original; con forma, se recorta en círculo o se deja cuadrado
+/// - Las líneas de texto se renderizan con `template_context`
+pub async fn render_card(
+    card: &WelcomeCard,
+    texts: &[WelcomeText],
+    template_context: &TemplateContext,
+    user: &User,
+    default_background: &str,
+    output_path: &str,
+) -> UnwrapResult<()> {
+    let background_path = card.background
+        .as_deref()
+        .filter(|path| Path::new(path).exists())
+        .unwrap_or(default_background);
+
+    let mut background = image::open(background_path)?;
+    let x = card.avatar_x.as_deref().and_then(|x| x.parse().ok()).unwrap_or(DEFAULT_AVATAR_X);
+    let y = card.avatar_y.as_deref().and_then(|y| y.parse().ok()).unwrap_or(DEFAULT_AVATAR_Y);
+    let size = card.avatar_size.as_deref().and_then(|size| size.parse().ok()).unwrap_or(DEFAULT_AVATAR_SIZE);
+
+    // Descarga la imagen del avatar
+    let response = get(user.face()).await?;
+    let bytes = response.bytes().await?;
+    let avatar = image::load_from_memory(&bytes)?;
+
+    match card.avatar_shape.as_deref().map(AvatarShape::from) {
+        None => combine_images(&mut background, &avatar, x, y, size)?,
+        Some(shape) => paste_avatar(&mut background, &avatar, x, y, size, shape),
+    }
+
+    for line in texts {
+        draw_text_line(&mut background, line, &template_context.render(&line.text))?;
+    }
+
+    background.save(output_path)?;
+
+    Ok(())
+}
+
+/// # Pega el avatar redimensionado con la forma indicada
+fn paste_avatar(background: &mut DynamicImage, avatar: &DynamicImage, x: u32, y: u32, size: u32, shape: AvatarShape) {
+    let mut avatar = imageops::resize(avatar, size, size, imageops::Lanczos3);
+    if shape == AvatarShape::Circle {
+        let radius = f64::from(size) / 2.0;
+        for (pixel_x, pixel_y, pixel) in avatar.enumerate_pixels_mut() {
+            let distance = (f64::from(pixel_x) + 0.5 - radius).hypot(f64::from(pixel_y) + 0.5 - radius);
+            if distance > radius { pixel.0[3] = 0 }
+        }
+    }
+
+    imageops::overlay(background, &avatar, i64::from(x), i64::from(y));
+}
+
+/// # Dibuja una línea de texto con su fuente, color y posición
+fn draw_text_line(background: &mut DynamicImage, line: &WelcomeText, text: &str) -> UnwrapResult<()> {
+    let font_data = std::fs::read(line.font.path())?;
+    let font = Font::try_from_vec(font_data).into_result()?;
+    let [_, red, green, blue] = parse_hex_color(&line.color).unwrap_or(0x00FF_FFFF).to_be_bytes();
+    let scale = Scale::uniform(line.size as f32);
+    draw_text_mut(background, Rgba([red, green, blue, 255]), line.x, line.y, scale, &font, text);
+
+    Ok(())
+}
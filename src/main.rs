@@ -15,10 +15,17 @@ use reqwest::Client as HttpClient;
 use handlers::error::handler;
 use handlers::events::event_handler;
 use handlers::misc::link_spam_handler::message_tracker_cleaner;
+use handlers::misc::native_automod::resync_enabled_guilds;
+use handlers::misc::phishing::phishing_list_watcher;
+use handlers::misc::auto_role::PendingAutoRole;
+use handlers::misc::captcha::CaptchaEntry;
+use handlers::misc::punishment::QuarantineEntry;
+use handlers::typing_start::rearm_exception_timers;
 use utils::Data;
 use utils::debug::UnwrapResult;
 use utils::load_commands;
 use utils::MessageData;
+use utils::scheduler::spawn_scheduler;
 
 pub static DB: LazyLock<Surreal<SurrealClient>> = LazyLock::new(Surreal::init);
 
@@ -56,6 +63,9 @@ async fn main() -> UnwrapResult<()> {
     // Limpiar el Tracker de mensajes de spam cada 5 segundos
     message_tracker_cleaner();
 
+    // Cargar la lista de dominios de phishing y recargarla cuando cambie el archivo
+    phishing_list_watcher();
+
     let token = dotenvy::var("DISCORD_TOKEN").expect("missing DISCORD_TOKEN");
     let intents = serenity::GatewayIntents::all() | serenity::GatewayIntents::MESSAGE_CONTENT;
 
@@ -76,8 +86,23 @@ async fn main() -> UnwrapResult<()> {
 
             ..Default::default()
         })
-        .setup(|ctx, _ready, framework| {
+        .setup(|ctx, ready, framework| {
             Box::pin(async move {
+                // Reprogramar las excepciones dinámicas que seguían activas antes de reiniciar el Bot
+                rearm_exception_timers(ctx.http.clone()).await?;
+
+                // Reprogramar los cambios de disponibilidad en las reglas de AutoMod de Discord
+                resync_enabled_guilds(&ctx.http, ready.guilds.iter().map(|guild| guild.id)).await;
+
+                // Terminar las cuarentenas caducadas, incluidas las que vencieron con el Bot apagado
+                spawn_scheduler::<QuarantineEntry>(ctx.http.clone());
+
+                // Asignar los roles automáticos pendientes, incluidos los que vencieron con el Bot apagado
+                spawn_scheduler::<PendingAutoRole>(ctx.http.clone());
+
+                // Expulsar a los miembros que no resolvieron el captcha a tiempo
+                spawn_scheduler::<CaptchaEntry>(ctx.http.clone());
+
                 poise::builtins::register_globally(
                     ctx,
                     &framework.options().commands
@@ -149,6 +174,23 @@ async fn create_database() -> UnwrapResult<()> {
     DB.query("DEFINE FIELD forbidden ON guild_config FLEXIBLE TYPE option<object>;").await?;
     DB.query("DEFINE FIELD messages ON guild_config FLEXIBLE TYPE option<object>;").await?;
     DB.query("DEFINE FIELD time_out ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD ghost_ping ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD mass_mention ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD warn_embed ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD dry_run ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD spam ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD flood ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD invite_filter ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD image_spam ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD raid ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD account_age ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD text_filter ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD quarantine ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD automod ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD native_automod ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD welcome_card ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD goodbye ON guild_config FLEXIBLE TYPE option<object>;").await?;
+    DB.query("DEFINE FIELD captcha ON guild_config FLEXIBLE TYPE option<object>;").await?;
     DB.query("DEFINE INDEX guild_id ON TABLE guild_config COLUMNS guild_id UNIQUE;").await?;
 
     Ok(())
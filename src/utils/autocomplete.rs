@@ -3,6 +3,9 @@ use futures::{
     Stream,
     StreamExt
 };
+use crate::commands::moderation::automod::AutomodRule;
+use crate::commands::moderation::filter::FilterRule;
+use crate::commands::moderation::welcome::WelcomeText;
 use crate::utils::Context;
 
 pub async fn args_set_timeout_timer<'a>(
@@ -33,4 +36,46 @@ pub async fn lessons<'a>(
 
     futures::stream::iter(files)
         .filter(move |data: &String| futures::future::ready(data.starts_with(partial)))
+}
+
+/// # Reglas del filtro de palabras del servidor
+pub async fn filter_rules<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Stream<Item = String> + 'a {
+    let rules = match ctx.guild_id() {
+        Some(guild_id) => FilterRule::get_rules(guild_id).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    futures::stream::iter(rules.into_iter().map(|rule| rule.pattern))
+        .filter(move |pattern: &String| futures::future::ready(pattern.starts_with(partial)))
+}
+
+/// # Reglas de moderación automática del servidor
+pub async fn automod_rules<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Stream<Item = String> + 'a {
+    let rules = match ctx.guild_id() {
+        Some(guild_id) => AutomodRule::get_rules(guild_id).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    futures::stream::iter(rules.into_iter().map(|rule| rule.name))
+        .filter(move |name: &String| futures::future::ready(name.starts_with(partial)))
+}
+
+/// # Líneas de texto de la tarjeta de bienvenida del servidor
+pub async fn welcome_texts<'a>(
+    ctx: Context<'_>,
+    partial: &'a str,
+) -> impl Stream<Item = String> + 'a {
+    let texts = match ctx.guild_id() {
+        Some(guild_id) => WelcomeText::get_texts(guild_id).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    futures::stream::iter(texts.into_iter().map(|line| line.text))
+        .filter(move |text: &String| futures::future::ready(text.starts_with(partial)))
 }
\ No newline at end of file
@@ -43,7 +43,9 @@ macro_rules! obj {
 
 macro_rules! build_obj {
     ($name:ident, $($field:ident: $type:ty),*) => {
+        // `default` permite leer configuraciones antiguas que no tienen las secciones añadidas después
         #[derive(Serialize, Deserialize, Debug, Clone, Default)]
+        #[serde(default)]
         pub struct $name {
             $(pub $field: $type,)*
             pub guild_id: Option<String>,
@@ -88,14 +90,48 @@ macro_rules! build_obj {
 }
 
 obj!(Admin, role: String, role_2: String);
-obj!(Forbidden, user: String, role: String);
-obj!(TimeOut, time: String);
+obj!(Forbidden, user: String, role: String, reply_ping: String);
+obj!(TimeOut, time: String, exception: String);
 obj!(Channels, welcome: String, ooc: String, logs: String, exceptions: String);
 obj!(Messages, welcome: String, time_out: String, warn: String);
+obj!(GhostPing, enabled: String, window: String);
+obj!(MassMention, enabled: String, per_message: String, per_window: String, window: String);
+obj!(WarnEmbed, image: String, color: String, footer: String);
+obj!(DryRun, modules: String);
+obj!(Spam, channels: String, window: String);
+obj!(Flood, enabled: String, action: String, rate: String, rate_window: String, similarity: String, similar_count: String, similar_window: String);
+obj!(InviteFilter, enabled: String);
+obj!(ImageSpam, enabled: String);
+obj!(Raid, enabled: String, joins: String, window: String, action: String);
+obj!(AccountAge, min_hours: String, action: String, role: String);
+obj!(Quarantine, enabled: String, role: String);
+obj!(Automod, enabled: String);
+obj!(NativeAutomod, enabled: String, mention_rule: String, word_rule: String);
+obj!(WelcomeCard, background: String, avatar_x: String, avatar_y: String, avatar_size: String, avatar_shape: String);
+obj!(Goodbye, enabled: String, channel: String, message: String, card: String);
+obj!(Captcha, enabled: String, role: String, verified_role: String, channel: String, timeout: String, attempts: String);
+obj!(TextFilter, enabled: String, action: String, caps_ratio: String, caps_min_length: String, emoji_max: String, zalgo_ratio: String, zalgo_min_length: String, exempt_channels: String);
 build_obj!(GuildData,
     admins: Admin,
     forbidden: Forbidden,
     time_out: TimeOut,
     channels: Channels,
-    messages: Messages
+    messages: Messages,
+    ghost_ping: GhostPing,
+    mass_mention: MassMention,
+    warn_embed: WarnEmbed,
+    dry_run: DryRun,
+    spam: Spam,
+    flood: Flood,
+    invite_filter: InviteFilter,
+    image_spam: ImageSpam,
+    raid: Raid,
+    account_age: AccountAge,
+    text_filter: TextFilter,
+    quarantine: Quarantine,
+    automod: Automod,
+    native_automod: NativeAutomod,
+    welcome_card: WelcomeCard,
+    goodbye: Goodbye,
+    captcha: Captcha
 );
\ No newline at end of file
@@ -1,10 +1,15 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::Utc;
 use plantita_audio::convert_to_mp3;
 use poise::serenity_prelude as serenity;
 use serenity::builder::{CreateEmbed, CreateEmbedFooter};
 use serenity::all::{ChannelId, CreateAttachment, CreateEmbedAuthor, CreateMessage, GuildId, Member, Message, Timestamp, UserId};
 
+use crate::handlers::misc::warns::MAX_WARNS;
+use crate::utils::config::GuildData;
+use crate::utils::debug::UnwrapResult;
+use crate::utils::template::TemplateContext;
+
 // LOS EMBEDS NO NOTIFICAN SI SE MENCIONA CON @ A UN USUARIO
 pub async fn edit_message_embed(
     ctx: &serenity::Context,
@@ -77,23 +82,58 @@ pub async fn send_embed_with_attachment(
     log_channel_id.send_message(&ctx.http, message_attachment).await
 }
 
+/// Imagen de consejo que se adjunta si el servidor no ha subido una propia
+pub const DEFAULT_WARN_IMAGE: &str = "./assets/sugerencia.png";
+
+/// Color del `Embed` de advertencia si el servidor no ha establecido uno
+pub const DEFAULT_WARN_COLOR: u32 = 0x00FF_FF00;
+
+/// Pie del `Embed` de advertencia si el servidor no ha establecido uno
+pub const DEFAULT_WARN_FOOTER: &str = "En el caso de recibir {max_warns} advertencias serás silenciado durante {duration}. Si estás respondiendo un mensaje considera responder sin el uso de \"@\".";
+
+/// # Envía el `Embed` de advertencia
+///
+/// - La imagen, el color y el pie se leen de la configuración del servidor
+/// - El pie se renderiza con `template_context`, por lo que refleja la sanción configurada
 pub async fn send_warn_embed(
     ctx: &serenity::Context,
+    guild_id: GuildId,
     warns: u8,
-    tip_image: &str,
     channel_id: ChannelId,
     warn_message: &str,
-) -> serenity::Result<Message> {
+    template_context: &TemplateContext,
+) -> UnwrapResult<Message> {
+    let warn_embed = GuildData::verify_data(guild_id).await?
+        .map(|data| data.warn_embed)
+        .unwrap_or_default();
 
-    let warn_message = format!("{warn_message}\n**Advertencia {warns}/3**");
-    let footer = "En el caso de recibir 3 advertencias serás silenciado por una semana. Si estás respondiendo un mensaje considera responder sin el uso de \"@\".";
+    let tip_image = warn_embed.image
+        .filter(|image| Path::new(image).exists())
+        .unwrap_or_else(|| DEFAULT_WARN_IMAGE.to_string());
+
+    let color = warn_embed.color
+        .and_then(|color| parse_hex_color(&color))
+        .unwrap_or(DEFAULT_WARN_COLOR);
+
+    let footer = template_context.render(warn_embed.footer.as_deref().unwrap_or(DEFAULT_WARN_FOOTER));
+    let warn_message = format!("{warn_message}\n**Advertencia {warns}/{MAX_WARNS}**");
     let attachment_image = CreateAttachment::path(tip_image).await?;
-    let embed = create_warn_embed(&warn_message,&attachment_image, footer);
+    let embed = create_warn_embed(&warn_message, &attachment_image, &footer, color);
     let builder = CreateMessage::default()
         .add_file(attachment_image)
         .embed(embed);
 
-    channel_id.send_message(&ctx.http, builder).await
+    Ok(channel_id.send_message(&ctx.http, builder).await?)
+}
+
+/// # Convierte un color hexadecimal a un número
+///
+/// - Acepta los formatos `#FFAA00` y `FFAA00`
+pub fn parse_hex_color(color: &str) -> Option<u32> {
+    let color = color.trim().trim_start_matches('#');
+    if color.len() != 6 { return None }
+
+    u32::from_str_radix(color, 16).ok()
 }
 
 fn create_embed_common(author_member: &Member, title: &str, description: &str, footer: &str) -> CreateEmbed {
@@ -107,10 +147,10 @@ fn create_embed_common(author_member: &Member, title: &str, description: &str, f
         .footer(CreateEmbedFooter::new(footer))
 }
 
-fn create_warn_embed(warn_message: &str, tip_image_mobile: &CreateAttachment, footer: &str) -> CreateEmbed {
+fn create_warn_embed(warn_message: &str, tip_image_mobile: &CreateAttachment, footer: &str, color: u32) -> CreateEmbed {
     CreateEmbed::default()
         .description(warn_message)
         .attachment(tip_image_mobile.filename.as_str())
-        .color(0x00FF_FF00)
+        .color(color)
         .footer(CreateEmbedFooter::new(footer))
 }
\ No newline at end of file
@@ -17,27 +17,55 @@ use crate::commands::fun::generate_furry::furry;
 use crate::commands::fun::generate_pride::pride;
 use crate::commands::fun::screenshot_this::screenshot_this;
 use crate::commands::info::help::help;
+use crate::commands::moderation::auto_role::autorole;
+use crate::commands::moderation::automod::automod;
+use crate::commands::moderation::filter::filter;
 use crate::commands::moderation::getters::get_admins::get_admins;
+use crate::commands::moderation::getters::get_availability::get_availability;
+use crate::commands::moderation::getters::get_domains::get_domains;
 use crate::commands::moderation::getters::get_exception_channel::get_exception_channel;
 use crate::commands::moderation::getters::get_forbidden_exception::get_forbidden_exception;
 use crate::commands::moderation::getters::get_forbidden_role::get_forbidden_role;
 use crate::commands::moderation::getters::get_forbidden_user::get_forbidden_user;
+use crate::commands::moderation::getters::get_invite_partners::get_invite_partners;
 use crate::commands::moderation::getters::get_log_channel::get_log_channel;
 use crate::commands::moderation::getters::get_ooc_channel::get_ooc_channel;
 use crate::commands::moderation::getters::get_timeout_timer::get_timeout_timer;
 use crate::commands::moderation::getters::get_welcome_channel::get_welcome_channel;
+use crate::commands::moderation::getters::preview_message::preview_message;
+use crate::commands::moderation::lockdown::lockdown;
+use crate::commands::moderation::setters::set_account_age::set_account_age;
 use crate::commands::moderation::setters::set_admins::set_admins;
+use crate::commands::moderation::setters::set_availability::{clear_availability, set_availability};
+use crate::commands::moderation::setters::set_captcha::set_captcha;
+use crate::commands::moderation::setters::set_domain::{set_domain, remove_domain};
+use crate::commands::moderation::setters::set_dry_run::set_dry_run;
 use crate::commands::moderation::setters::set_exception_channel::set_exception_channel;
+use crate::commands::moderation::setters::set_exception_timer::set_exception_timer;
+use crate::commands::moderation::setters::set_flood::set_flood;
 use crate::commands::moderation::setters::set_forbidden_exception::set_forbidden_exception;
 use crate::commands::moderation::setters::set_forbidden_role::set_forbidden_role;
 use crate::commands::moderation::setters::set_forbidden_user::set_forbidden_user;
+use crate::commands::moderation::setters::set_ghost_ping::set_ghost_ping;
+use crate::commands::moderation::setters::set_goodbye::set_goodbye;
+use crate::commands::moderation::setters::set_invite_filter::{set_invite_filter, add_invite_partner, remove_invite_partner};
+use crate::commands::moderation::setters::set_image_spam::set_image_spam;
 use crate::commands::moderation::setters::set_log_channel::set_log_channel;
+use crate::commands::moderation::setters::set_mass_mention::set_mass_mention;
+use crate::commands::moderation::setters::set_native_automod::set_native_automod;
 use crate::commands::moderation::setters::set_ooc_channel::set_ooc_channel;
+use crate::commands::moderation::setters::set_quarantine::{set_quarantine, release_quarantine};
+use crate::commands::moderation::setters::set_raid::set_raid;
+use crate::commands::moderation::setters::set_reply_ping_policy::set_reply_ping_policy;
+use crate::commands::moderation::setters::set_spam_thresholds::set_spam_thresholds;
+use crate::commands::moderation::setters::set_text_filter::{set_text_filter, set_text_filter_exemption};
 use crate::commands::moderation::setters::set_timeout_message::set_time_out_message;
 use crate::commands::moderation::setters::set_timeout_timer::set_timeout_timer;
+use crate::commands::moderation::setters::set_warn_embed::set_warn_embed;
 use crate::commands::moderation::setters::set_warn_message::set_warn_message;
 use crate::commands::moderation::setters::set_welcome_channel::set_welcome_channel;
 use crate::commands::moderation::setters::set_welcome_message::set_welcome_message;
+use crate::commands::moderation::welcome::welcome;
 use crate::commands::info::ping::ping;
 use crate::commands::lessons::rust::rust;
 use crate::DB;
@@ -46,6 +74,8 @@ pub mod autocomplete;
 pub mod config;
 pub mod debug;
 pub mod embeds;
+pub mod scheduler;
+pub mod template;
 
 #[allow(dead_code)]
 pub struct Data {
@@ -94,6 +124,18 @@ impl MessageData {
         Ok(existing_data)
     }
 
+    /// Sustituye el contenido guardado de un mensaje tras editarse
+    pub async fn update_content(&self, new_content: &str) -> SurrealResult<()> {
+        DB.use_ns("discord-namespace").use_db("discord").await?;
+        let sql_query = "UPDATE messages SET message_content = $message_content WHERE message_id = $message_id";
+        DB.query(sql_query)
+            .bind(("message_content", new_content))
+            .bind(("message_id", &self.message_id))
+            .await?;
+
+        Ok(())
+    }
+
     pub async fn get_audio_data(message_id: &MessageId) -> SurrealResult<Option<Self>> {
         DB.use_ns("discord-namespace").use_db("discord").await?;
         let sql_query = "SELECT * FROM audio WHERE message_id = $message_id";
@@ -186,9 +228,39 @@ pub fn load_commands() -> Vec<Command<Data, Error>> {
         set_forbidden_role(),
         set_welcome_message(),
         set_welcome_channel(),
+        welcome(),
+        set_goodbye(),
+        autorole(),
+        set_captcha(),
         set_time_out_message(),
         set_forbidden_exception(),
         set_exception_channel(),
+        set_exception_timer(),
+        set_ghost_ping(),
+        set_mass_mention(),
+        set_warn_embed(),
+        set_dry_run(),
+        set_spam_thresholds(),
+        set_domain(),
+        remove_domain(),
+        set_flood(),
+        set_invite_filter(),
+        add_invite_partner(),
+        remove_invite_partner(),
+        set_image_spam(),
+        set_raid(),
+        lockdown(),
+        set_account_age(),
+        filter(),
+        set_text_filter(),
+        set_text_filter_exemption(),
+        set_quarantine(),
+        release_quarantine(),
+        automod(),
+        set_native_automod(),
+        set_reply_ping_policy(),
+        set_availability(),
+        clear_availability(),
         get_admins(),
         get_log_channel(),
         get_ooc_channel(),
@@ -198,6 +270,10 @@ pub fn load_commands() -> Vec<Command<Data, Error>> {
         get_welcome_channel(),
         get_exception_channel(),
         get_forbidden_exception(),
+        get_availability(),
+        preview_message(),
+        get_domains(),
+        get_invite_partners(),
         screenshot_this(),
         pride(),
         furry(),
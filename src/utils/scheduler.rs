@@ -0,0 +1,48 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serenity::all::{Http, Timestamp};
+use surrealdb::Result as SurrealResult;
+
+use crate::log_handle;
+use crate::utils::Error;
+
+/// Intervalo con el que se revisan los registros programados
+const SCHEDULER_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// # Registro guardado en la base de datos que vence en un momento concreto
+///
+/// - `get_due` obtiene los registros que ya han vencido
+/// - `run` aplica el registro; si falla, se registra `error_message` junto al error
+/// - `FETCH_ERROR` se registra si no se pueden obtener los registros vencidos
+pub trait ScheduledEntry: Sized + Send + 'static {
+    const FETCH_ERROR: &'static str;
+
+    fn get_due(now: i64) -> impl Future<Output = SurrealResult<Vec<Self>>> + Send;
+    fn run(&self, http: &Http) -> impl Future<Output = Result<(), Error>> + Send;
+    fn error_message(&self) -> String;
+}
+
+/// # Revisa periódicamente los registros vencidos de un tipo
+///
+/// - Los registros se guardan en la base de datos, por lo que los que vencieron con el Bot apagado
+///   se aplican en la primera revisión
+/// - Un registro que falle no impide aplicar los demás
+pub fn spawn_scheduler<T: ScheduledEntry>(http: Arc<Http>) {
+    tokio::spawn(async move {
+        loop {
+            let now = Timestamp::now().unix_timestamp();
+            match T::get_due(now).await {
+                Ok(entries) => for entry in entries {
+                    if let Err(why) = entry.run(&http).await {
+                        log_handle!("{}: {why}", entry.error_message());
+                    }
+                },
+                Err(why) => log_handle!("{}: {why}", T::FETCH_ERROR),
+            }
+
+            tokio::time::sleep(SCHEDULER_CHECK_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use poise::serenity_prelude as serenity;
+use serenity::all::{ChannelId, GuildId, Member, User};
+
+/// Variables disponibles en los mensajes personalizados
+pub const PLACEHOLDERS: [&str; 9] = [
+    "user",
+    "mention",
+    "server",
+    "warns",
+    "max_warns",
+    "duration",
+    "member_count",
+    "channel",
+    "time_in_server",
+];
+
+/// # Crea los métodos para establecer cada variable de la plantilla
+///
+/// - Cada método sigue el patrón de diseño Builder
+macro_rules! placeholder {
+    ($($name:ident),*) => {
+        $(
+            pub fn $name(mut self, value: impl ToString) -> Self {
+                self.values.insert(stringify!($name), value.to_string());
+                self
+            }
+        )*
+    };
+}
+
+/// # Contexto para renderizar una plantilla
+///
+/// - Almacena el valor de cada variable de la plantilla
+/// - Las variables sin valor se dejan sin reemplazar
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    values: HashMap<&'static str, String>,
+}
+
+impl TemplateContext {
+    placeholder!(user, mention, server, warns, max_warns, duration, member_count, channel, time_in_server);
+
+    /// Crea un contexto con las variables del miembro y del servidor
+    pub fn for_member(ctx: &serenity::Context, member: &Member) -> Self {
+        Self::for_user(ctx, member.guild_id, &member.user).user(member.distinct())
+    }
+
+    /// # Crea un contexto con las variables de un usuario y del servidor
+    ///
+    /// - Se usa cuando el usuario ya no es miembro del servidor, por lo que no tiene apodo
+    pub fn for_user(ctx: &serenity::Context, guild_id: GuildId, user: &User) -> Self {
+        let guild = guild_id.to_guild_cached(&ctx.cache);
+        let server = guild.as_ref().map(|guild| guild.name.clone()).unwrap_or_default();
+        let member_count = guild.as_ref().map(|guild| guild.member_count).unwrap_or_default();
+        drop(guild);
+
+        Self::default()
+            .user(user.name.clone())
+            .mention(format!("<@{}>", user.id))
+            .server(server)
+            .member_count(member_count)
+    }
+
+    /// Establece la variable `{channel}` como una mención al canal
+    pub fn channel_id(self, channel_id: ChannelId) -> Self {
+        self.channel(format!("<#{channel_id}>"))
+    }
+
+    /// # Reemplaza las variables `{nombre}` de la plantilla
+    ///
+    /// - `{{` y `}}` se utilizan para escribir llaves literales
+    pub fn render(&self, template: &str) -> String {
+        let mut output = String::with_capacity(template.len());
+        let mut chars = template.chars().peekable();
+
+        while let Some(character) = chars.next() {
+            match character {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    output.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    output.push('}');
+                }
+                '{' => {
+                    let name = chars.by_ref().take_while(|character| *character != '}').collect::<String>();
+                    match self.values.get(name.as_str()) {
+                        Some(value) => output.push_str(value),
+                        None => output.push_str(&format!("{{{name}}}")),
+                    }
+                }
+                character => output.push(character),
+            }
+        }
+
+        output
+    }
+}
+
+/// # Valida una plantilla antes de guardarla
+///
+/// - Todas las llaves deben estar cerradas
+/// - Todas las variables deben existir en `PLACEHOLDERS`
+pub fn validate_template(template: &str) -> Result<(), String> {
+    let mut chars = template.chars().peekable();
+    while let Some(character) = chars.next() {
+        match character {
+            '{' if chars.peek() == Some(&'{') => { chars.next(); }
+            '}' if chars.peek() == Some(&'}') => { chars.next(); }
+            '{' => {
+                let mut name = String::new();
+                let mut closed = false;
+                for character in chars.by_ref() {
+                    if character == '}' {
+                        closed = true;
+                        break
+                    }
+                    name.push(character);
+                }
+
+                if !closed {
+                    return Err("Hay una llave `{` sin cerrar".to_string())
+                }
+
+                if !PLACEHOLDERS.contains(&name.as_str()) {
+                    return Err(format!("La variable `{{{name}}}` no existe. Variables disponibles: {}", available_placeholders()))
+                }
+            }
+            '}' => return Err("Hay una llave `}` sin abrir. Usa `}}` para escribir una llave".to_string()),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Devuelve la lista de variables disponibles con formato `{nombre}`
+pub fn available_placeholders() -> String {
+    PLACEHOLDERS
+        .iter()
+        .map(|name| format!("`{{{name}}}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// # Adapta los mensajes guardados antes de existir las plantillas
+///
+/// - Antes se añadía el nombre del usuario delante del mensaje desde el código
+/// - Si el mensaje no tiene ninguna variable, se le antepone `prefix` para conservar ese comportamiento
+pub fn upgrade_legacy(template: &str, prefix: &str) -> String {
+    let has_placeholders = PLACEHOLDERS
+        .iter()
+        .any(|name| template.contains(&format!("{{{name}}}")));
+
+    if has_placeholders {
+        template.to_string()
+    } else {
+        format!("{prefix}{template}")
+    }
+}
+
+/// # Convierte una cantidad de segundos a un texto legible
+///
+/// - Ejemplo: `5400` -> `1 hora y 30 minutos`
+pub fn format_duration(seconds: i64) -> String {
+    const UNITS: [(i64, &str, &str); 5] = [
+        (604_800, "semana", "semanas"),
+        (86_400, "día", "días"),
+        (3_600, "hora", "horas"),
+        (60, "minuto", "minutos"),
+        (1, "segundo", "segundos"),
+    ];
+
+    let mut remaining = seconds.max(0);
+    let mut parts = Vec::new();
+    for (unit_seconds, singular, plural) in UNITS {
+        let amount = remaining / unit_seconds;
+        remaining %= unit_seconds;
+        if amount > 0 {
+            parts.push(format!("{amount} {}", if amount == 1 { singular } else { plural }));
+        }
+    }
+
+    match parts.len() {
+        0 => "0 segundos".to_string(),
+        1 => parts.remove(0),
+        _ => {
+            let last = parts.pop().unwrap_or_default();
+            format!("{} y {last}", parts.join(", "))
+        }
+    }
+}